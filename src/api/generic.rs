@@ -3,6 +3,7 @@ use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
 
+use crate::api::ruleset::{handle_ruleset_request, RulesetRequest, RulesetResponse};
 use crate::api::token::AuthToken;
 use crate::api::user::{handle_user_request, UserRequest, UserResponse};
 use crate::database::connection_pool::ConnectionPool;
@@ -28,6 +29,7 @@ pub struct TokenPacket<T> {
 pub enum RequestData {
     Ping(i64),
     User(UserRequest),
+    Ruleset(RulesetRequest),
 }
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum ResponseData {
@@ -38,6 +40,7 @@ pub enum ResponseData {
         error_text: String,
     },
     User(UserResponse),
+    Ruleset(RulesetResponse),
 }
 impl<E> From<E> for ResponseData where E: ReadableError {
     fn from(from: E) -> Self {
@@ -64,5 +67,6 @@ pub async fn handle_request(request: Request, pool: Arc<ConnectionPool>) -> Resp
             data: ResponseData::Pong(value),
         },
         RequestData::User(user_request) => handle_user_request(request.token, user_request, pool).await,
+        RequestData::Ruleset(ruleset_request) => handle_ruleset_request(ruleset_request),
     }
 }
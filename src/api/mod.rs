@@ -2,11 +2,18 @@ use std::sync::Arc;
 
 use crate::api::generic::{handle_request, Request};
 use crate::database::connection_pool::ConnectionPool;
+use crate::game::ruleset::Ruleset;
 
 pub mod generic;
+pub mod ruleset;
 pub mod token;
 pub mod user;
 
 pub async fn api_handler(request: Request, pool: Arc<ConnectionPool>) -> Result<impl warp::Reply, warp::Rejection> {
     Ok(warp::reply::json(&handle_request(request, pool).await))
 }
+
+/// Serves `Ruleset::json_schema` on its own so tools can point at it.
+pub fn ruleset_schema_handler() -> impl warp::Reply {
+    warp::reply::json(&Ruleset::json_schema())
+}
@@ -0,0 +1,24 @@
+use schemars::schema::RootSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::api::generic::{Response, ResponseData};
+use crate::game::ruleset::Ruleset;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum RulesetRequest {
+    /// The JSON Schema rulesets must follow, see `Ruleset::json_schema`
+    Schema,
+}
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum RulesetResponse {
+    Schema(Box<RootSchema>),
+}
+
+pub fn handle_ruleset_request(request: RulesetRequest) -> Response {
+    match request {
+        RulesetRequest::Schema => Response {
+            token: None,
+            data: ResponseData::Ruleset(RulesetResponse::Schema(Box::new(Ruleset::json_schema()))),
+        },
+    }
+}
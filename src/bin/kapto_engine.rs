@@ -0,0 +1,22 @@
+use std::io::{BufRead, stdin, stdout, Write};
+
+use kapto_web::engine::Engine;
+
+/// Runs the engine protocol over stdin and stdout, see `Engine`.
+fn main() {
+    let mut engine = Engine::new();
+    let stdin = stdin();
+    let stdout = stdout();
+    let mut out = stdout.lock();
+    for line in stdin.lock().lines() {
+        let line = line.expect("Could not read from stdin");
+        let answer = match engine.handle(&line) {
+            None => break,
+            Some(answer) => answer,
+        };
+        for answer_line in answer {
+            writeln!(out, "{}", answer_line).expect("Could not write to stdout");
+        }
+        out.flush().expect("Could not write to stdout");
+    }
+}
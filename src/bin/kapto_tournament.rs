@@ -0,0 +1,61 @@
+use std::env;
+use std::fs;
+use std::process;
+use std::time::Duration;
+
+use kapto_web::game::ai::SearchBudget;
+use kapto_web::game::ruleset::Ruleset;
+use kapto_web::tournament::{run_tournament, TournamentConfig};
+
+const USAGE: &str = "Usage: kapto_tournament [--games n] [--threads n] [--nodes n | --movetime ms | --depth n] [--mcts] \
+[--max-plies n] [--random-plies n] [--seed n] [--weights file] <ruleset.json>...";
+
+/// Plays AI against AI games for each ruleset file and prints a report for each.
+fn main() {
+    let mut config = TournamentConfig::default();
+    let mut paths = Vec::new();
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut number = || args.next().and_then(|value| value.parse::<u64>().ok()).unwrap_or_else(|| fail(USAGE));
+        match arg.as_str() {
+            "--games" => config.games = number() as usize,
+            "--threads" => config.threads = number() as usize,
+            "--nodes" => config.budget = SearchBudget::nodes(number()),
+            "--movetime" => config.budget = SearchBudget::time(Duration::from_millis(number())),
+            "--depth" => config.budget = SearchBudget::depth(number() as usize),
+            "--max-plies" => config.max_plies = number() as usize,
+            "--random-plies" => config.random_plies = number() as usize,
+            "--seed" => config.seed = number(),
+            "--mcts" => config.mcts = true,
+            "--weights" => {
+                let path = args.next().unwrap_or_else(|| fail(USAGE));
+                config.weights = serde_json::from_str(&read(&path)).unwrap_or_else(|error| fail(&format!("{}: {}", path, error)));
+            }
+            _ if arg.starts_with("--") => fail(USAGE),
+            _ => paths.push(arg),
+        }
+    }
+    if paths.is_empty() {
+        fail(USAGE);
+    }
+
+    for path in paths {
+        let ruleset: Ruleset = serde_json::from_str(&read(&path)).unwrap_or_else(|error| fail(&format!("{}: {}", path, error)));
+        if let Err(error) = ruleset.verify() {
+            fail(&format!("{}: {}", path, error));
+        }
+        match run_tournament(&ruleset, &config) {
+            Ok(report) => print!("{}", report),
+            Err(error) => fail(&format!("{}: {}", path, error)),
+        }
+    }
+}
+
+fn read(path: &str) -> String {
+    fs::read_to_string(path).unwrap_or_else(|error| fail(&format!("{}: {}", path, error)))
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}", message);
+    process::exit(1)
+}
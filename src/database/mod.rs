@@ -8,6 +8,7 @@ use mysql_async::prelude::FromValue;
 use serde::{Deserialize, Serialize};
 
 use crate::api::generic::ReadableError;
+use crate::game::ruleset::migration::MigrationError;
 
 pub mod connection_pool;
 pub mod paging;
@@ -25,6 +26,8 @@ pub enum DatabaseError {
     MysqlError(String),
     SerdeJsonError(String),
     TokenFailed,
+    /// A stored ruleset could not be migrated and loaded
+    MigrationError(String),
 }
 impl From<OsString> for DatabaseError{
     fn from(from: OsString) -> Self {
@@ -41,6 +44,11 @@ impl From<serde_json::Error> for DatabaseError {
         Self::SerdeJsonError(format!("{:?}", from))
     }
 }
+impl From<MigrationError> for DatabaseError {
+    fn from(from: MigrationError) -> Self {
+        Self::MigrationError(format!("{:?}", from))
+    }
+}
 impl fmt::Display for DatabaseError {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         <Self as Debug>::fmt(self, f)
@@ -55,6 +63,7 @@ impl ReadableError for DatabaseError {
             DatabaseError::MysqlError(error) => format!("Database Error: {}", error),
             DatabaseError::SerdeJsonError(error) => format!("Serialization Error: {}", error),
             Self::TokenFailed => "Token verification failed".to_string(),
+            Self::MigrationError(error) => format!("Ruleset could not be loaded: {}", error),
         }
     }
 }
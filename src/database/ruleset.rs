@@ -5,26 +5,28 @@ use crate::database::{DatabaseResult, get_from_row};
 use crate::database::DatabaseError::*;
 use crate::database::paging::Paging;
 use crate::database::procedures::Procedure;
+use crate::game::ruleset::migration::{load_ruleset, RULESET_VERSION};
 use crate::game::ruleset::Ruleset;
 
 pub struct DatabaseRuleset {
     pub id: u64,
     pub user_id: u64,
+    /// Schema version of the stored json, `RULESET_VERSION` once loaded as loading migrates the ruleset
     pub version: u32,
     pub ruleset: Ruleset,
 }
 impl DatabaseRuleset {
     pub async fn by_id(connection: &mut Conn, id: u64) -> DatabaseResult<Option<Self>>{
-        Procedure::GetRulesetById.exec_first(connection, vec![
+        Procedure::GetRulesetById.exec_first::<RulesetRow, _>(connection, vec![
             ("id", id.into()),
-        ]).await
+        ]).await?.map(RulesetRow::load).transpose()
     }
     pub async fn for_user(connection: &mut Conn, user_id: u64, paging: Paging) -> DatabaseResult<Vec<Self>>{
-        Procedure::GetRulesetsForUser.exec(connection, vec![
+        Procedure::GetRulesetsForUser.exec::<RulesetRow, _>(connection, vec![
             ("user_id", user_id.into()),
             ("limit", paging.limit.into()),
             ("offset", paging.offset.into()),
-        ]).await
+        ]).await?.into_iter().map(RulesetRow::load).collect()
     }
     pub async fn delete(connection: &mut Conn, id: u64) -> DatabaseResult<()>{
         Procedure::DeleteRuleset.exec_drop(connection, vec![
@@ -32,31 +34,42 @@ impl DatabaseRuleset {
         ]).await
     }
 
+    /// Stores the ruleset as `RULESET_VERSION`, whatever `version` says.
     pub async fn insert_into_database(self, connection: &mut Conn) -> DatabaseResult<Self>{
-        match Procedure::InsertRuleset.exec_first(connection, vec![
+        match Procedure::InsertRuleset.exec_first::<RulesetRow, _>(connection, vec![
             ("user_id", self.user_id.into()),
-            ("version", self.version.into()),
+            ("version", RULESET_VERSION.into()),
             ("ruleset", serde_json::to_string(&self.ruleset)?.into())
         ]).await?{
             None => Err(NotFound),
-            Some(ruleset) => Ok(ruleset),
+            Some(ruleset) => ruleset.load(),
         }
     }
 }
-impl FromRow for DatabaseRuleset {
+
+/// A ruleset row before its json is migrated, so migration errors reach the caller.
+struct RulesetRow {
+    id: u64,
+    user_id: u64,
+    version: u32,
+    ruleset: String,
+}
+impl RulesetRow {
+    fn load(self) -> DatabaseResult<DatabaseRuleset> {
+        Ok(DatabaseRuleset{
+            id: self.id,
+            user_id: self.user_id,
+            version: RULESET_VERSION,
+            ruleset: load_ruleset(&self.ruleset, self.version)?,
+        })
+    }
+}
+impl FromRow for RulesetRow {
     fn from_row_opt(row: Row) -> Result<Self, FromRowError> where Self: Sized {
         let (id, row) = get_from_row(row, 0)?;
         let (user_id, row) = get_from_row(row, 1)?;
         let (version, row) = get_from_row(row, 2)?;
-        let (ruleset_raw, row) = get_from_row::<String>(row, 3)?;
-
-        let ruleset = match serde_json::from_str::<Ruleset>(&ruleset_raw){
-            Ok(ruleset) => ruleset,
-            Err(error) => {
-                eprintln!("Error deserializing ruleset id {}: {}, text: {}", id, error, ruleset_raw);
-                return Err(FromRowError(row));
-            },
-        };
+        let (ruleset, _) = get_from_row(row, 3)?;
 
         Ok(Self{
             id,
@@ -0,0 +1,316 @@
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Debug, Display, Formatter};
+use std::fs;
+use std::time::Duration;
+
+use crate::game::ai::{alpha_beta, mcts, SearchBudget};
+use crate::game::ai::evaluation::EvaluationWeights;
+use crate::game::ai::mcts::MctsConfig;
+use crate::game::board::BoardError;
+use crate::game::hidden_placement::check_hidden_placement;
+use crate::game::notation::{NotatedMove, NotationError, RecordEntry};
+use crate::game::position::{format_position, parse_position};
+use crate::game::ruleset::{Ruleset, RulesetError};
+use crate::game::ruleset::standard::standard_rules;
+use crate::game::state::{Action, GameState, StateError};
+
+/// A line based engine protocol for bots and GUIs, in the spirit of UCI.
+///
+/// Each command is one line and answered with zero or more lines, failures are answered with `error <reason>`.
+/// - `kapto`: answered with `id name kapto_web` then `kaptook`.
+/// - `isready`: answered with `readyok`.
+/// - `ruleset standard`, `ruleset file <path>`, or `ruleset json <json>`: loads and verifies a ruleset and resets
+///   the position to its start.
+/// - `weights <json>`: sets the `EvaluationWeights`, missing weights use the defaults.
+/// - `position start [moves <entry>...]` or `position string <position> [moves <entry>...]`: sets the position to
+///   the start or a position string, then plays the record entries, see `RecordEntry` and `format_position`.
+/// - `go [movetime <ms>] [nodes <n>] [depth <n>] [mcts] [seed <n>]`: searches the position, alpha-beta unless
+///   `mcts` is given, answered with an `info` line then `bestmove <entry>` or `bestmove none`. Without a limit
+///   alpha-beta visits `alpha_beta::DEFAULT_NODES` positions and mcts runs `mcts::DEFAULT_ITERATIONS` iterations.
+/// - `display`: answered with `position <position string>`.
+/// - `quit`: stops the engine.
+pub struct Engine {
+    ruleset: Ruleset,
+    state: GameState,
+    weights: EvaluationWeights,
+}
+impl Engine {
+    /// Starts at the beginning of a standard game.
+    pub fn new() -> Self {
+        let ruleset = standard_rules();
+        Self {
+            state: GameState::new(&ruleset).expect("Standard rules have a valid start"),
+            ruleset,
+            weights: EvaluationWeights::default(),
+        }
+    }
+
+    /// Handles one command, returning the lines to answer with or `None` once the engine should stop.
+    pub fn handle(&mut self, line: &str) -> Option<Vec<String>> {
+        let mut words = line.split_whitespace();
+        let command = match words.next() {
+            None => return Some(Vec::new()),
+            Some(command) => command,
+        };
+        let rest: Vec<_> = words.collect();
+        let result = match command {
+            "quit" => return None,
+            "kapto" => Ok(vec!["id name kapto_web".to_string(), "kaptook".to_string()]),
+            "isready" => Ok(vec!["readyok".to_string()]),
+            "ruleset" => self.load_ruleset(line, &rest).map(|_| Vec::new()),
+            "weights" => serde_json::from_str(after_words(line, 1))
+                .map(|weights| {
+                    self.weights = weights;
+                    Vec::new()
+                })
+                .map_err(|error| EngineError::InvalidJson(error.to_string())),
+            "position" => self.set_position(&rest).map(|_| Vec::new()),
+            "go" => self.go(&rest),
+            "display" => Ok(vec![format!("position {}", format_position(self.state.board()))]),
+            _ => Err(EngineError::UnknownCommand(command.to_string())),
+        };
+        Some(result.unwrap_or_else(|error| vec![format!("error {}", error)]))
+    }
+
+    fn load_ruleset(&mut self, line: &str, args: &[&str]) -> EngineResult<()> {
+        let ruleset: Ruleset = match args.first() {
+            Some(&"standard") => standard_rules(),
+            Some(&"file") => {
+                let text = fs::read_to_string(after_words(line, 2)).map_err(|error| EngineError::Io(error.to_string()))?;
+                serde_json::from_str(&text).map_err(|error| EngineError::InvalidJson(error.to_string()))?
+            }
+            Some(&"json") => serde_json::from_str(after_words(line, 2)).map_err(|error| EngineError::InvalidJson(error.to_string()))?,
+            _ => return Err(EngineError::InvalidArguments(line.to_string())),
+        };
+        ruleset.verify().map_err(|error| EngineError::RulesetError(Box::new(error)))?;
+        self.state = GameState::new(&ruleset)?;
+        self.ruleset = ruleset;
+        Ok(())
+    }
+
+    fn set_position(&mut self, args: &[&str]) -> EngineResult<()> {
+        let invalid = || EngineError::InvalidArguments(args.join(" "));
+        let (mut state, moves) = match args.first() {
+            Some(&"start") => (GameState::new(&self.ruleset)?, &args[1..]),
+            Some(&"string") if args.len() >= 6 => {
+                let board = parse_position(&self.ruleset, &args[1..6].join(" "))?;
+                (GameState::from_board(&self.ruleset, board), &args[6..])
+            }
+            _ => return Err(invalid()),
+        };
+        let moves = match moves.split_first() {
+            None => moves,
+            Some((&"moves", moves)) => moves,
+            Some(_) => return Err(invalid()),
+        };
+        for &text in moves {
+            let entry: RecordEntry = text.parse()?;
+            let action = Action::from_entry(&entry);
+            let legal = match &entry {
+                RecordEntry::Move(notated) => {
+                    state.legal_actions(&self.ruleset).contains(&action)
+                        && NotatedMove::new(&self.ruleset, state.board(), notated.action.clone())? == *notated
+                }
+                RecordEntry::Place { .. } | RecordEntry::FinishPlacement => state.legal_actions(&self.ruleset).contains(&action),
+                RecordEntry::HiddenPlacement { seat, pieces } => check_hidden_placement(&self.ruleset, state.board(), *seat, pieces).is_ok(),
+            };
+            if !legal {
+                return Err(EngineError::IllegalMove(text.to_string()));
+            }
+            state.apply(&self.ruleset, action)?;
+        }
+        self.state = state;
+        Ok(())
+    }
+
+    fn go(&mut self, args: &[&str]) -> EngineResult<Vec<String>> {
+        let invalid = || EngineError::InvalidArguments(args.join(" "));
+        let mut budget = SearchBudget::default();
+        let mut use_mcts = false;
+        let mut config = MctsConfig::default();
+        let mut words = args.iter();
+        while let Some(&word) = words.next() {
+            let mut number = || words.next().and_then(|value| value.parse::<u64>().ok()).ok_or_else(invalid);
+            match word {
+                "movetime" => budget.time = Some(Duration::from_millis(number()?)),
+                "nodes" => budget.nodes = Some(number()?),
+                "depth" => budget.depth = Some(number()? as usize),
+                "seed" => config.seed = number()?,
+                "mcts" => use_mcts = true,
+                _ => return Err(invalid()),
+            }
+        }
+
+        let (action, info) = if use_mcts {
+            let result = mcts::search(&self.ruleset, &mut self.state, budget, config, &self.weights);
+            (result.action, format!("info iterations {} visits {}", result.iterations, result.visits))
+        } else {
+            let result = alpha_beta::search(&self.ruleset, &mut self.state, budget, &self.weights);
+            (result.action, format!("info depth {} score {} nodes {}", result.depth, result.score, result.nodes))
+        };
+        let best = match action {
+            None => "none".to_string(),
+            Some(action) => self.entry(action)?.to_string(),
+        };
+        Ok(vec![info, format!("bestmove {}", best)])
+    }
+
+    /// The record entry for playing `action` in the current position.
+    fn entry(&self, action: Action) -> EngineResult<RecordEntry> {
+        Ok(match action {
+            Action::Move(action) => RecordEntry::Move(NotatedMove::new(&self.ruleset, self.state.board(), action)?),
+            Action::Place { coordinate, piece } => RecordEntry::Place { coordinate, piece },
+            Action::FinishPlacement => RecordEntry::FinishPlacement,
+            Action::HiddenPlacement { seat, pieces } => RecordEntry::HiddenPlacement { seat, pieces },
+        })
+    }
+}
+impl Default for Engine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The text of `line` after its first `count` words.
+fn after_words(line: &str, count: usize) -> &str {
+    let mut rest = line.trim_start();
+    for _ in 0..count {
+        rest = rest.trim_start_matches(|c: char| !c.is_whitespace()).trim_start();
+    }
+    rest.trim_end()
+}
+
+pub type EngineResult<T> = Result<T, EngineError>;
+#[derive(Clone, Debug)]
+pub enum EngineError {
+    UnknownCommand(String),
+    InvalidArguments(String),
+    InvalidJson(String),
+    Io(String),
+    IllegalMove(String),
+    RulesetError(Box<RulesetError>),
+    NotationError(NotationError),
+    BoardError(BoardError),
+    StateError(StateError),
+}
+impl Display for EngineError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        <Self as Debug>::fmt(self, f)
+    }
+}
+impl Error for EngineError {
+    fn cause(&self) -> Option<&dyn Error> {
+        match self {
+            Self::RulesetError(error) => Some(error.as_ref()),
+            Self::NotationError(error) => Some(error),
+            Self::BoardError(error) => Some(error),
+            Self::StateError(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+impl From<NotationError> for EngineError {
+    fn from(from: NotationError) -> Self {
+        Self::NotationError(from)
+    }
+}
+impl From<BoardError> for EngineError {
+    fn from(from: BoardError) -> Self {
+        Self::BoardError(from)
+    }
+}
+impl From<StateError> for EngineError {
+    fn from(from: StateError) -> Self {
+        Self::StateError(from)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use crate::engine::Engine;
+    use crate::game::ai::alpha_beta::DEFAULT_NODES;
+    use crate::game::Coordinate;
+    use crate::game::ruleset::standard::standard_rules;
+    use crate::game::ruleset::starting_positions::alteration_type::AlternationType;
+    use crate::game::ruleset::starting_positions::placement_area::PlacementArea;
+    use crate::game::ruleset::starting_positions::StartingPositions;
+
+    fn send(engine: &mut Engine, line: &str) -> Vec<String> {
+        engine.handle(line).expect("Engine quit")
+    }
+
+    #[test]
+    fn handshake() {
+        let mut engine = Engine::new();
+        assert_eq!(send(&mut engine, "kapto"), vec!["id name kapto_web", "kaptook"]);
+        assert_eq!(send(&mut engine, "isready"), vec!["readyok"]);
+        assert_eq!(send(&mut engine, ""), Vec::<String>::new());
+        assert!(send(&mut engine, "fly")[0].starts_with("error UnknownCommand"));
+        assert_eq!(engine.handle("quit"), None);
+    }
+
+    #[test]
+    fn position_and_go() {
+        let mut engine = Engine::new();
+        assert_eq!(send(&mut engine, "ruleset file example/standard_rules.json"), Vec::<String>::new());
+        assert_eq!(send(&mut engine, "ruleset standard"), Vec::<String>::new());
+        let start = send(&mut engine, "display");
+
+        let answer = send(&mut engine, "go depth 1");
+        assert!(answer[0].starts_with("info depth 1 "));
+        let best = answer[1].strip_prefix("bestmove ").unwrap().to_string();
+        assert_eq!(send(&mut engine, &format!("position start moves {}", best)), Vec::<String>::new());
+        assert_ne!(send(&mut engine, "display"), start);
+
+        let position = start[0].strip_prefix("position ").unwrap();
+        assert_eq!(send(&mut engine, &format!("position string {} moves {}", position, best)), Vec::<String>::new());
+        let answer = send(&mut engine, "go mcts nodes 5 seed 3");
+        assert!(answer[1].starts_with("bestmove "));
+
+        // The second move starts from the space the first one left
+        assert!(send(&mut engine, &format!("position start moves {} {}", best, best))[0].starts_with("error IllegalMove"));
+        assert!(send(&mut engine, "go depth")[0].starts_with("error InvalidArguments"));
+    }
+
+    #[test]
+    fn hidden_placement_entries() {
+        let mut ruleset = standard_rules();
+        let area = |row| (0..10).map(|column| Coordinate::new(row, column)).collect::<HashSet<_>>();
+        ruleset.starting_positions = StartingPositions::Placement {
+            first_seat: 0,
+            alternation_type: AlternationType::Hidden,
+            placement_area: PlacementArea::NonMirrored(vec![area(5), area(6)]),
+            piece_limits: HashSet::new(),
+        };
+        let mut engine = Engine::new();
+        assert_eq!(send(&mut engine, &format!("ruleset json {}", serde_json::to_string(&ruleset).unwrap())), Vec::<String>::new());
+        assert!(send(&mut engine, "position start moves 9{}")[0].starts_with("error IllegalMove"));
+        assert!(send(&mut engine, "position start moves 0{0@a1}")[0].starts_with("error IllegalMove"));
+        assert_eq!(send(&mut engine, "position start moves 0{0@a5} 1{0@b6}"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn go_without_limit() {
+        let mut engine = Engine::new();
+        let answer = send(&mut engine, "go");
+        let nodes: u64 = answer[0].rsplit(' ').next().unwrap().parse().unwrap();
+        assert!(nodes <= DEFAULT_NODES);
+        assert!(answer[1].starts_with("bestmove ") && answer[1] != "bestmove none");
+    }
+
+    #[test]
+    fn weights_and_rulesets() {
+        let mut engine = Engine::new();
+        assert_eq!(send(&mut engine, r#"weights {"mobility": 0}"#), Vec::<String>::new());
+        assert!(send(&mut engine, "weights {")[0].starts_with("error InvalidJson"));
+        assert_eq!(send(&mut engine, "ruleset file example/test_rules.json"), Vec::<String>::new());
+        let mut ruleset = standard_rules();
+        ruleset.seats = 1;
+        let json = serde_json::to_string(&ruleset).unwrap();
+        assert!(send(&mut engine, &format!("ruleset json {}", json))[0].starts_with("error RulesetError"));
+        assert!(send(&mut engine, "ruleset file missing.json")[0].starts_with("error Io"));
+    }
+}
@@ -0,0 +1,293 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use crate::game::ai::{Clock, SearchBudget};
+use crate::game::ai::evaluation::Evaluator;
+use crate::game::capture::is_capture;
+use crate::game::ruleset::board_type::space::Space;
+use crate::game::ruleset::draw_rule::DrawRule;
+use crate::game::ruleset::Ruleset;
+use crate::game::state::{Action, GameState};
+use crate::game::victory::{evaluate_game, is_game_over, SeatOutcome};
+
+/// Score of a won game, reduced by the plies it takes so quicker wins are preferred.
+pub const WIN_SCORE: i64 = 1_000_000;
+/// Deepest search iterative deepening will start.
+pub const MAX_DEPTH: usize = 64;
+/// Positions to visit when the budget has no time, node, or depth limit.
+pub const DEFAULT_NODES: u64 = 10_000;
+
+/// The outcome of a search.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SearchResult {
+    /// Best action found, `None` if the game is over or there is nothing to do
+    pub action: Option<Action>,
+    /// Score of the action for the searching seat's team, see `Evaluator`
+    pub score: i64,
+    /// Deepest completed search
+    pub depth: usize,
+    /// Positions visited
+    pub nodes: u64,
+}
+
+/// Finds the best action for the seat to move with iterative deepening alpha-beta, scoring leaves with `evaluator`.
+///
+/// Seats allied with the seat to move maximize its score and every other seat minimizes it, so games with more than
+/// two teams are searched as if every other team works together.
+/// Captures and moves into a goal of the mover's team are searched first, after the best action of the last search.
+/// Jumps that give the same board are searched once, see `GameState::distinct_actions`.
+/// Once the budget runs out the best action of the deepest completed search is returned, or the first ordered action
+/// if none completed. The state is left as it was.
+pub fn search(ruleset: &Ruleset, state: &mut GameState, budget: SearchBudget, evaluator: &dyn Evaluator) -> SearchResult {
+    let mut budget = budget;
+    if budget.time.is_none() && budget.nodes.is_none() && budget.depth.is_none() {
+        budget.nodes = Some(DEFAULT_NODES);
+    }
+    let mut search = Search {
+        ruleset,
+        evaluator,
+        seat: state.board().current_seat(),
+        clock: Clock::new(budget),
+        table: HashMap::new(),
+        history_draws: ruleset.draw_rules.iter().any(|rule| matches!(rule, DrawRule::Repetition(_) | DrawRule::NoCapture(_))),
+        stopped: false,
+    };
+    let mut result = SearchResult {
+        action: None,
+        score: 0,
+        depth: 0,
+        nodes: 0,
+    };
+    let outcomes = evaluate_game(ruleset, state);
+    if is_game_over(&outcomes) || !outcomes[search.seat as usize].is_playing() {
+        return result;
+    }
+    result.action = search.ordered_actions(state, None).into_iter().next();
+    if result.action.is_none() {
+        return result;
+    }
+
+    let max_depth = budget.depth.unwrap_or(MAX_DEPTH).min(MAX_DEPTH);
+    for depth in 1..=max_depth {
+        let score = search.alpha_beta(state, depth, 0, -WIN_SCORE - 1, WIN_SCORE + 1);
+        if search.stopped {
+            break;
+        }
+        result.score = score;
+        result.depth = depth;
+        result.action = search.table.get(&state.hash()).and_then(|entry| entry.best.clone());
+        if is_mate(score) || search.clock.is_out() {
+            break;
+        }
+    }
+    result.nodes = search.clock.nodes();
+    result
+}
+
+/// Whether `score` is a won or lost game rather than an evaluation.
+fn is_mate(score: i64) -> bool {
+    score.abs() >= WIN_SCORE - MAX_DEPTH as i64
+}
+/// Converts a mate score counted from the root into one counted from the node `ply` plies in, for storing.
+fn to_table(score: i64, ply: usize) -> i64 {
+    match score {
+        score if is_mate(score) => score + score.signum() * ply as i64,
+        score => score,
+    }
+}
+/// Converts a stored mate score back into one counted from the root, reached `ply` plies in.
+fn from_table(score: i64, ply: usize) -> i64 {
+    match score {
+        score if is_mate(score) => score - score.signum() * ply as i64,
+        score => score,
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Bound {
+    Exact,
+    /// The score is at least this
+    Lower,
+    /// The score is at most this
+    Upper,
+}
+
+#[derive(Clone, Debug)]
+struct Entry {
+    depth: usize,
+    score: i64,
+    bound: Bound,
+    best: Option<Action>,
+}
+
+struct Search<'a> {
+    ruleset: &'a Ruleset,
+    evaluator: &'a dyn Evaluator,
+    /// Seat the scores are for
+    seat: u64,
+    clock: Clock,
+    /// Transposition table keyed by Zobrist hash, mate scores are stored counted from their node
+    table: HashMap<u64, Entry>,
+    /// Whether the draw rules depend on how a position was reached, which the hash does not cover.
+    /// Stored scores are then only used for move ordering.
+    history_draws: bool,
+    stopped: bool,
+}
+impl Search<'_> {
+    fn alpha_beta(&mut self, state: &mut GameState, depth: usize, ply: usize, mut alpha: i64, mut beta: i64) -> i64 {
+        self.clock.visit();
+        if self.clock.is_out() {
+            self.stopped = true;
+            return 0;
+        }
+        if ply > 0 {
+            let outcomes = evaluate_game(self.ruleset, state);
+            match &outcomes[self.seat as usize] {
+                SeatOutcome::Playing => {}
+                SeatOutcome::Won(_) => return WIN_SCORE - ply as i64,
+                SeatOutcome::Lost(_) => return ply as i64 - WIN_SCORE,
+                SeatOutcome::Drawn(_) => return 0,
+            }
+        }
+        if depth == 0 {
+            return self.evaluator.evaluate(self.ruleset, state.board(), self.seat);
+        }
+
+        let hash = state.hash();
+        let mut best_action = None;
+        if let Some(entry) = self.table.get(&hash) {
+            if ply > 0 && entry.depth >= depth && !self.history_draws {
+                let score = from_table(entry.score, ply);
+                match entry.bound {
+                    Bound::Exact => return score,
+                    Bound::Lower => alpha = alpha.max(score),
+                    Bound::Upper => beta = beta.min(score),
+                }
+                if alpha >= beta {
+                    return score;
+                }
+            }
+            best_action = entry.best.clone();
+        }
+        let actions = self.ordered_actions(state, best_action.as_ref());
+        if actions.is_empty() {
+            return self.evaluator.evaluate(self.ruleset, state.board(), self.seat);
+        }
+
+        let maximizing = self.ruleset.are_allied(state.board().current_seat(), self.seat);
+        let (start_alpha, start_beta) = (alpha, beta);
+        let mut best = if maximizing { i64::MIN } else { i64::MAX };
+        for action in actions {
+            state.apply(self.ruleset, action.clone()).expect("Legal action could not be applied");
+            let score = self.alpha_beta(state, depth - 1, ply + 1, alpha, beta);
+            state.undo();
+            if self.stopped {
+                return 0;
+            }
+            if (maximizing && score > best) || (!maximizing && score < best) {
+                best = score;
+                best_action = Some(action);
+            }
+            if maximizing {
+                alpha = alpha.max(best);
+            } else {
+                beta = beta.min(best);
+            }
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        let bound = if best <= start_alpha {
+            Bound::Upper
+        } else if best >= start_beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        self.table.insert(hash, Entry { depth, score: to_table(best, ply), bound, best: best_action });
+        best
+    }
+
+    /// Legal actions with `first`, then captures, then moves into a goal, then the rest.
+    fn ordered_actions(&self, state: &GameState, first: Option<&Action>) -> Vec<Action> {
+        let board = state.board();
+        let mut actions = state.distinct_actions(self.ruleset);
+        actions.sort_by_cached_key(|action| Reverse(match action {
+            action if Some(action) == first => 3,
+            Action::Move(action) if is_capture(self.ruleset, board, action) => 2,
+            Action::Move(action) => match action.to().map(|to| self.ruleset.board_type.get_space(to)) {
+                Some(Space::Goal(seat)) if self.ruleset.are_allied(seat, board.current_seat()) => 1,
+                _ => 0,
+            },
+            _ => 0,
+        }));
+        actions
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::game::ai::alpha_beta::{from_table, search, to_table, WIN_SCORE};
+    use crate::game::ai::evaluation::EvaluationWeights;
+    use crate::game::ai::SearchBudget;
+    use crate::game::board::{Board, BoardPiece};
+    use crate::game::Coordinate;
+    use crate::game::movement::Move;
+    use crate::game::ruleset::standard::standard_rules;
+    use crate::game::state::{Action, GameState};
+
+    #[test]
+    fn finds_winning_move() {
+        let ruleset = standard_rules();
+        let mut board = Board::new(&ruleset);
+        // Seat 0 holds one goal and is a step from the other
+        board.place(&ruleset, Coordinate::new(11, 4), BoardPiece::new(0, 1)).unwrap();
+        board.place(&ruleset, Coordinate::new(10, 6), BoardPiece::new(0, 1)).unwrap();
+        board.place(&ruleset, Coordinate::new(2, 0), BoardPiece::new(1, 1)).unwrap();
+        board.place(&ruleset, Coordinate::new(2, 9), BoardPiece::new(1, 1)).unwrap();
+        let mut state = GameState::from_board(&ruleset, board.clone());
+
+        let result = search(&ruleset, &mut state, SearchBudget::depth(3), &EvaluationWeights::default());
+        assert_eq!(result.action, Some(Action::Move(Move::Step { from: Coordinate::new(10, 6), to: Coordinate::new(11, 5) })));
+        assert_eq!(result.score, WIN_SCORE - 1);
+        assert_eq!(state.board(), &board);
+    }
+
+    #[test]
+    fn table_mate_scores() {
+        // A win 5 plies from the root found 2 plies in is 3 plies from its node, and 4 from a transposition 1 ply deeper
+        assert_eq!(to_table(WIN_SCORE - 5, 2), WIN_SCORE - 3);
+        assert_eq!(from_table(WIN_SCORE - 3, 1), WIN_SCORE - 4);
+        assert_eq!(from_table(to_table(7 - WIN_SCORE, 3), 4), 8 - WIN_SCORE);
+        assert_eq!(from_table(to_table(250, 3), 4), 250);
+    }
+
+    #[test]
+    fn takes_capture() {
+        let ruleset = standard_rules();
+        let mut board = Board::new(&ruleset);
+        board.place(&ruleset, Coordinate::new(5, 5), BoardPiece::new(0, 0)).unwrap();
+        board.place(&ruleset, Coordinate::new(6, 5), BoardPiece::new(1, 1)).unwrap();
+        board.place(&ruleset, Coordinate::new(1, 1), BoardPiece::new(1, 1)).unwrap();
+        let mut state = GameState::from_board(&ruleset, board);
+
+        let result = search(&ruleset, &mut state, SearchBudget::depth(2), &EvaluationWeights::default());
+        assert_eq!(result.action, Some(Action::Move(Move::Jump { path: vec![Coordinate::new(5, 5), Coordinate::new(7, 5)] })));
+    }
+
+    #[test]
+    fn respects_budget() {
+        let ruleset = standard_rules();
+        let mut state = GameState::new(&ruleset).unwrap();
+        let result = search(&ruleset, &mut state, SearchBudget::nodes(500), &EvaluationWeights::default());
+        assert!(result.nodes <= 500);
+        assert!(state.legal_actions(&ruleset).contains(&result.action.unwrap()));
+
+        let result = search(&ruleset, &mut state, SearchBudget::time(Duration::from_millis(50)), &EvaluationWeights::default());
+        assert!(state.legal_actions(&ruleset).contains(&result.action.unwrap()));
+        assert_eq!(state.ply(), 0);
+    }
+}
@@ -0,0 +1,154 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::board::Board;
+use crate::game::Coordinate;
+use crate::game::movement::distinct_moves;
+use crate::game::ruleset::board_type::space::Space;
+use crate::game::ruleset::Ruleset;
+use crate::game::ruleset::victory_condition::VictoryCondition;
+
+/// Scores boards for the AI.
+pub trait Evaluator {
+    /// Scores `board` for `seat`'s team, higher is better.
+    ///
+    /// Scores should be far below `alpha_beta::WIN_SCORE` so won games always score higher.
+    fn evaluate(&self, ruleset: &Ruleset, board: &Board, seat: u64) -> i64;
+}
+
+/// The default evaluation, a weighted sum of features counted for `seat`'s team and against every other team.
+///
+/// Missing fields are filled with the defaults when deserializing so a config only needs the weights it changes.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EvaluationWeights {
+    /// Per point of piece value on the board, see `Ruleset::piece_value`
+    pub material: i64,
+    /// Per space closer to the nearest goal of the piece's team, for pieces that count for a `GoalCount` condition
+    pub goal_distance: i64,
+    /// Per legal move, jumps with the same landing spot and captures count once, see `distinct_moves`
+    pub mobility: i64,
+    /// Per piece an enemy can capture next turn, counted against the piece's team
+    pub threatened: i64,
+}
+impl Default for EvaluationWeights {
+    fn default() -> Self {
+        Self {
+            material: 100,
+            goal_distance: 10,
+            mobility: 1,
+            threatened: 40,
+        }
+    }
+}
+impl Evaluator for EvaluationWeights {
+    fn evaluate(&self, ruleset: &Ruleset, board: &Board, seat: u64) -> i64 {
+        let sign = |other: u64| if ruleset.are_allied(seat, other) { 1 } else { -1 };
+        let mut score = 0;
+
+        if self.material != 0 {
+            score += self.material * board.pieces()
+                .map(|(_, piece)| sign(piece.seat) * ruleset.piece_value(piece.piece) as i64)
+                .sum::<i64>();
+        }
+
+        let goal_pieces: HashSet<_> = ruleset.victory_conditions.iter()
+            .filter_map(|condition| match condition {
+                VictoryCondition::GoalCount { valid_pieces, .. } => Some(valid_pieces),
+                _ => None,
+            })
+            .flatten()
+            .copied()
+            .collect();
+        if self.goal_distance != 0 && !goal_pieces.is_empty() {
+            let goals = goals(ruleset, board);
+            let span = board.rows().max(board.columns()) as i64;
+            score += self.goal_distance * board.pieces()
+                .filter(|(_, piece)| goal_pieces.contains(&piece.piece))
+                .map(|(position, piece)| {
+                    let progress = goals.iter()
+                        .filter(|(_, goal_seat)| ruleset.are_allied(*goal_seat, piece.seat))
+                        .map(|(goal, _)| span - distance(position, *goal))
+                        .max()
+                        .unwrap_or(0);
+                    sign(piece.seat) * progress
+                })
+                .sum::<i64>();
+        }
+
+        if self.mobility != 0 || self.threatened != 0 {
+            let mut threatened = HashSet::new();
+            for mover in 0..ruleset.seats {
+                let moves = distinct_moves(ruleset, board, mover);
+                score += self.mobility * sign(mover) * moves.len() as i64;
+                threatened.extend(moves.into_iter()
+                    .flat_map(|(_, captured)| captured)
+                    .filter(|capture| !ruleset.are_allied(mover, capture.piece.seat)));
+            }
+            score -= self.threatened * threatened.iter().map(|capture| sign(capture.piece.seat)).sum::<i64>();
+        }
+        score
+    }
+}
+
+/// Every goal on the board with the seat it belongs to.
+fn goals(ruleset: &Ruleset, board: &Board) -> Vec<(Coordinate, u64)> {
+    let mut out = Vec::new();
+    for row in 0..board.rows() {
+        for column in 0..board.columns() {
+            let coordinate = Coordinate::new(row as i16, column as i16);
+            if let Space::Goal(seat) = ruleset.board_type.get_space(coordinate) {
+                out.push((coordinate, seat));
+            }
+        }
+    }
+    out
+}
+/// Number of king moves between two spaces.
+fn distance(from: Coordinate, to: Coordinate) -> i64 {
+    ((from.row - to.row).abs().max((from.column - to.column).abs())) as i64
+}
+
+#[cfg(test)]
+mod test {
+    use crate::game::ai::evaluation::{EvaluationWeights, Evaluator};
+    use crate::game::board::{Board, BoardPiece};
+    use crate::game::Coordinate;
+    use crate::game::ruleset::standard::standard_rules;
+
+    const NONE: EvaluationWeights = EvaluationWeights { material: 0, goal_distance: 0, mobility: 0, threatened: 0 };
+
+    #[test]
+    fn features() {
+        let ruleset = standard_rules();
+        let mut board = Board::new(&ruleset);
+        board.place(&ruleset, Coordinate::new(5, 5), BoardPiece::new(0, 0)).unwrap();
+        board.place(&ruleset, Coordinate::new(4, 5), BoardPiece::new(0, 1)).unwrap();
+        board.place(&ruleset, Coordinate::new(6, 5), BoardPiece::new(1, 1)).unwrap();
+
+        let material = EvaluationWeights { material: 1, ..NONE };
+        assert_eq!(material.evaluate(&ruleset, &board, 0), 1);
+        assert_eq!(material.evaluate(&ruleset, &board, 1), -1);
+
+        // Seat 0 pieces are 6 and 7 rows from row 11, the seat 1 piece is 6 rows from row 0
+        let goal_distance = EvaluationWeights { goal_distance: 1, ..NONE };
+        assert_eq!(goal_distance.evaluate(&ruleset, &board, 0), (12 - 6) + (12 - 7) - (12 - 6));
+
+        // Seat 0 is forced to capture, its three jump paths end on 7,5 or 3,5, seat 1 can step anywhere but 5,5
+        let mobility = EvaluationWeights { mobility: 1, ..NONE };
+        assert_eq!(mobility.evaluate(&ruleset, &board, 0), 2 - 7);
+
+        let threatened = EvaluationWeights { threatened: 1, ..NONE };
+        assert_eq!(threatened.evaluate(&ruleset, &board, 0), 1);
+        assert_eq!(threatened.evaluate(&ruleset, &board, 1), -1);
+    }
+
+    #[test]
+    fn partial_config() {
+        let weights: EvaluationWeights = serde_json::from_str(r#"{"mobility": 0, "threatened": 5}"#).unwrap();
+        assert_eq!(weights, EvaluationWeights { mobility: 0, threatened: 5, ..EvaluationWeights::default() });
+        let json = serde_json::to_string(&weights).unwrap();
+        assert_eq!(serde_json::from_str::<EvaluationWeights>(&json).unwrap(), weights);
+    }
+}
@@ -0,0 +1,302 @@
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+use crate::game::ai::{Clock, SearchBudget};
+use crate::game::ai::evaluation::Evaluator;
+use crate::game::ruleset::Ruleset;
+use crate::game::state::{Action, GameState};
+use crate::game::victory::{evaluate_game, is_game_over, SeatOutcome};
+
+/// Iterations to run when the budget has no time or node limit.
+pub const DEFAULT_ITERATIONS: u64 = 1000;
+
+/// Settings for a Monte Carlo tree search.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct MctsConfig {
+    /// UCT exploration constant
+    pub exploration: f64,
+    /// Plies a random playout runs before the board is scored by the evaluator
+    pub playout_limit: usize,
+    /// Seed for every random choice, the same seed and node budget give the same result
+    pub seed: u64,
+}
+impl Default for MctsConfig {
+    fn default() -> Self {
+        Self {
+            exploration: std::f64::consts::SQRT_2,
+            playout_limit: 100,
+            seed: 0,
+        }
+    }
+}
+
+/// The outcome of a Monte Carlo tree search.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MctsResult {
+    /// Most visited action, `None` if the game is over or there is nothing to do
+    pub action: Option<Action>,
+    /// Average reward of the action for each seat, indexed by seat
+    pub rewards: Vec<f64>,
+    /// Times the action was visited
+    pub visits: u64,
+    /// Iterations run, each visits one node of the budget
+    pub iterations: u64,
+}
+
+/// Finds the best action for the seat to move with Monte Carlo tree search.
+///
+/// Every playout gives each seat a reward between 0 and 1: 1 for a win, 0 for a loss, and 0.5 for a draw. Playouts
+/// cut off by `MctsConfig::playout_limit` are scored with `evaluator` for each seat instead.
+/// Allied seats share results so they get the same reward, and at each node the seat to move picks the child best for
+/// itself, which works for any number of seats and teams.
+/// Jumps that give the same board are one action, see `GameState::distinct_actions`.
+/// The state is left as it was.
+pub fn search(ruleset: &Ruleset, state: &mut GameState, budget: SearchBudget, config: MctsConfig, evaluator: &dyn Evaluator) -> MctsResult {
+    let mut budget = budget;
+    if budget.time.is_none() && budget.nodes.is_none() {
+        budget.nodes = Some(DEFAULT_ITERATIONS);
+    }
+    let mut search = Search {
+        ruleset,
+        evaluator,
+        config,
+        rng: StdRng::seed_from_u64(config.seed),
+        nodes: vec![Node::new(None, state.board().current_seat(), state.distinct_actions(ruleset), ruleset.seats)],
+    };
+    let mut result = MctsResult {
+        action: None,
+        rewards: vec![0.0; ruleset.seats as usize],
+        visits: 0,
+        iterations: 0,
+    };
+    if search.nodes[0].untried.is_empty() || is_game_over(&evaluate_game(ruleset, state)) {
+        return result;
+    }
+
+    let mut clock = Clock::new(budget);
+    while !clock.is_out() {
+        search.iterate(state);
+        clock.visit();
+    }
+    result.iterations = clock.nodes();
+    let best = search.nodes[0].children.iter()
+        .map(|&child| &search.nodes[child])
+        .max_by_key(|node| node.visits);
+    if let Some(best) = best {
+        result.action = best.action.clone();
+        result.rewards = best.rewards.iter().map(|reward| reward / best.visits as f64).collect();
+        result.visits = best.visits;
+    }
+    result
+}
+
+#[derive(Clone, Debug)]
+struct Node {
+    /// Action leading here from the parent
+    action: Option<Action>,
+    /// Seat to move at this node
+    seat: u64,
+    /// Actions not yet expanded
+    untried: Vec<Action>,
+    children: Vec<usize>,
+    visits: u64,
+    /// Reward totals for each seat
+    rewards: Vec<f64>,
+}
+impl Node {
+    fn new(action: Option<Action>, seat: u64, untried: Vec<Action>, seats: u64) -> Self {
+        Self {
+            action,
+            seat,
+            untried,
+            children: Vec::new(),
+            visits: 0,
+            rewards: vec![0.0; seats as usize],
+        }
+    }
+}
+
+struct Search<'a> {
+    ruleset: &'a Ruleset,
+    evaluator: &'a dyn Evaluator,
+    config: MctsConfig,
+    rng: StdRng,
+    /// The tree, the root is at 0
+    nodes: Vec<Node>,
+}
+impl Search<'_> {
+    /// Runs one selection, expansion, playout, and backpropagation.
+    fn iterate(&mut self, state: &mut GameState) {
+        let mut path = vec![0];
+        let mut applied = 0;
+        let mut rewards = None;
+        loop {
+            let current = path[path.len() - 1];
+            if let Some(outcome) = self.outcome_rewards(state) {
+                rewards = Some(outcome);
+                break;
+            }
+            if !self.nodes[current].untried.is_empty() {
+                let index = self.rng.gen_range(0..self.nodes[current].untried.len());
+                let action = self.nodes[current].untried.swap_remove(index);
+                state.apply(self.ruleset, action.clone()).expect("Legal action could not be applied");
+                applied += 1;
+                let node = Node::new(Some(action), state.board().current_seat(), state.distinct_actions(self.ruleset), self.ruleset.seats);
+                self.nodes.push(node);
+                let child = self.nodes.len() - 1;
+                self.nodes[current].children.push(child);
+                path.push(child);
+                break;
+            }
+            let child = match self.select(current) {
+                None => break,
+                Some(child) => child,
+            };
+            let action = self.nodes[child].action.clone().expect("Child nodes have an action");
+            state.apply(self.ruleset, action).expect("Legal action could not be applied");
+            applied += 1;
+            path.push(child);
+        }
+
+        let rewards = match rewards {
+            Some(rewards) => rewards,
+            None => self.playout(state),
+        };
+        for &index in &path {
+            let node = &mut self.nodes[index];
+            node.visits += 1;
+            node.rewards.iter_mut().zip(&rewards).for_each(|(total, reward)| *total += reward);
+        }
+        for _ in 0..applied {
+            state.undo();
+        }
+    }
+
+    /// The child with the best UCT value for the seat to move at `parent`.
+    fn select(&self, parent: usize) -> Option<usize> {
+        let node = &self.nodes[parent];
+        let log_visits = (node.visits as f64).ln();
+        let uct = |child: usize| {
+            let child = &self.nodes[child];
+            child.rewards[node.seat as usize] / child.visits as f64
+                + self.config.exploration * (log_visits / child.visits as f64).sqrt()
+        };
+        node.children.iter().copied().max_by(|&a, &b| uct(a).partial_cmp(&uct(b)).unwrap_or(std::cmp::Ordering::Equal))
+    }
+
+    /// Plays random actions until the game ends or the playout limit, then undoes them.
+    fn playout(&mut self, state: &mut GameState) -> Vec<f64> {
+        let mut applied = 0;
+        let rewards = loop {
+            if let Some(rewards) = self.outcome_rewards(state) {
+                break rewards;
+            }
+            let mut actions = state.distinct_actions(self.ruleset);
+            if applied >= self.config.playout_limit || actions.is_empty() {
+                break self.evaluate_rewards(state);
+            }
+            let action = actions.swap_remove(self.rng.gen_range(0..actions.len()));
+            state.apply(self.ruleset, action).expect("Legal action could not be applied");
+            applied += 1;
+        };
+        for _ in 0..applied {
+            state.undo();
+        }
+        rewards
+    }
+
+    /// Rewards for each seat if the game is over.
+    fn outcome_rewards(&self, state: &GameState) -> Option<Vec<f64>> {
+        let outcomes = evaluate_game(self.ruleset, state);
+        if !is_game_over(&outcomes) {
+            return None;
+        }
+        Some(outcomes.iter()
+            .map(|outcome| match outcome {
+                SeatOutcome::Won(_) => 1.0,
+                SeatOutcome::Lost(_) => 0.0,
+                SeatOutcome::Playing | SeatOutcome::Drawn(_) => 0.5,
+            })
+            .collect())
+    }
+    /// Rewards for each seat from the evaluator, a score of 100 is worth about 0.73.
+    fn evaluate_rewards(&self, state: &GameState) -> Vec<f64> {
+        (0..self.ruleset.seats)
+            .map(|seat| 1.0 / (1.0 + (-(self.evaluator.evaluate(self.ruleset, state.board(), seat) as f64) / 100.0).exp()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ndarray::Array2;
+
+    use crate::game::ai::evaluation::EvaluationWeights;
+    use crate::game::ai::mcts::{MctsConfig, search};
+    use crate::game::ai::SearchBudget;
+    use crate::game::board::{Board, BoardPiece};
+    use crate::game::Coordinate;
+    use crate::game::movement::Move;
+    use crate::game::ruleset::board_type::BoardType;
+    use crate::game::ruleset::board_type::space::Space;
+    use crate::game::ruleset::color::ColorBuiltIn;
+    use crate::game::ruleset::Ruleset;
+    use crate::game::ruleset::standard::standard_rules;
+    use crate::game::ruleset::starting_positions::StartingPositions;
+    use crate::game::ruleset::victory_condition::VictoryCondition;
+    use crate::game::state::{Action, GameState};
+
+    /// Three seats on a 5 by 5 board with seats 0 and 2 allied, each seat has one goal.
+    fn three_seats() -> Ruleset {
+        let mut spaces = Array2::from_elem((5, 5), Space::Normal);
+        spaces[(4, 4)] = Space::Goal(0);
+        spaces[(0, 2)] = Space::Goal(1);
+        spaces[(2, 0)] = Space::Goal(2);
+        Ruleset {
+            name: "Three".to_string(),
+            seats: 3,
+            allies: vec![vec![0, 2].into_iter().collect()],
+            seat_colors: vec![ColorBuiltIn::Red.into(), ColorBuiltIn::Blue.into(), ColorBuiltIn::Green.into()],
+            board_type: BoardType::Custom(spaces),
+            starting_positions: StartingPositions::NotMirrored(vec![vec![vec![], vec![]]; 3]),
+            victory_conditions: vec![VictoryCondition::GoalCount {
+                amount: 1,
+                valid_pieces: vec![0, 1].into_iter().collect(),
+            }].into_iter().collect(),
+            ..standard_rules()
+        }
+    }
+
+    #[test]
+    fn allied_seat_takes_goal() {
+        let ruleset = three_seats();
+        let mut board = Board::new(&ruleset);
+        board.place(&ruleset, Coordinate::new(0, 0), BoardPiece::new(0, 1)).unwrap();
+        board.place(&ruleset, Coordinate::new(1, 2), BoardPiece::new(1, 1)).unwrap();
+        board.place(&ruleset, Coordinate::new(3, 1), BoardPiece::new(2, 1)).unwrap();
+        board.set_current_seat(2);
+        let mut state = GameState::from_board(&ruleset, board.clone());
+
+        let result = search(&ruleset, &mut state, SearchBudget::nodes(300), MctsConfig::default(), &EvaluationWeights::default());
+        assert_eq!(result.action, Some(Action::Move(Move::Step { from: Coordinate::new(3, 1), to: Coordinate::new(2, 0) })));
+        assert_eq!(result.rewards, vec![1.0, 0.0, 1.0]);
+        assert_eq!(result.iterations, 300);
+        assert_eq!(state.board(), &board);
+    }
+
+    #[test]
+    fn same_seed_same_result() {
+        let ruleset = three_seats();
+        let mut board = Board::new(&ruleset);
+        for &(row, column, seat) in &[(0, 0, 0), (0, 4, 0), (4, 0, 1), (4, 3, 1), (2, 4, 2), (3, 3, 2)] {
+            board.place(&ruleset, Coordinate::new(row, column), BoardPiece::new(seat, 0)).unwrap();
+        }
+        let mut state = GameState::from_board(&ruleset, board);
+        let config = MctsConfig { playout_limit: 20, seed: 7, ..MctsConfig::default() };
+        let first = search(&ruleset, &mut state, SearchBudget::nodes(100), config, &EvaluationWeights::default());
+        let second = search(&ruleset, &mut state, SearchBudget::nodes(100), config, &EvaluationWeights::default());
+        assert_eq!(first, second);
+        assert!(state.legal_actions(&ruleset).contains(&first.action.unwrap()));
+        assert_eq!(state.ply(), 0);
+    }
+}
@@ -0,0 +1,53 @@
+use std::time::{Duration, Instant};
+
+pub mod alpha_beta;
+pub mod evaluation;
+pub mod mcts;
+
+/// Limits on a search, it stops at whichever is reached first.
+///
+/// Each search picks its own limit for an empty budget.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct SearchBudget {
+    /// Time to search for
+    pub time: Option<Duration>,
+    /// Positions to visit
+    pub nodes: Option<u64>,
+    /// Deepest search to complete
+    pub depth: Option<usize>,
+}
+impl SearchBudget {
+    pub fn time(time: Duration) -> Self {
+        Self { time: Some(time), ..Self::default() }
+    }
+    pub fn nodes(nodes: u64) -> Self {
+        Self { nodes: Some(nodes), ..Self::default() }
+    }
+    pub fn depth(depth: usize) -> Self {
+        Self { depth: Some(depth), ..Self::default() }
+    }
+}
+
+/// Tracks how much of a budget a search has used.
+#[derive(Debug)]
+pub(crate) struct Clock {
+    budget: SearchBudget,
+    start: Instant,
+    nodes: u64,
+}
+impl Clock {
+    pub(crate) fn new(budget: SearchBudget) -> Self {
+        Self { budget, start: Instant::now(), nodes: 0 }
+    }
+    pub(crate) fn visit(&mut self) {
+        self.nodes += 1;
+    }
+    pub(crate) fn nodes(&self) -> u64 {
+        self.nodes
+    }
+    /// Whether the time or node budget is used up.
+    pub(crate) fn is_out(&self) -> bool {
+        self.budget.nodes.map_or(false, |nodes| self.nodes >= nodes)
+            || self.budget.time.map_or(false, |time| self.start.elapsed() >= time)
+    }
+}
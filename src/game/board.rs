@@ -0,0 +1,293 @@
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Debug, Display, Formatter};
+
+use ndarray::Array2;
+use serde::{Deserialize, Serialize};
+
+use crate::game::Coordinate;
+use crate::game::placement::PlacementState;
+use crate::game::ruleset::board_type::space::Space;
+use crate::game::ruleset::Ruleset;
+use crate::game::ruleset::starting_positions::StartingPositions;
+
+/// A piece sitting on the board.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct BoardPiece {
+    /// The seat that owns the piece
+    pub seat: u64,
+    /// Index into `Ruleset::pieces`
+    pub piece: usize,
+}
+impl BoardPiece {
+    pub fn new(seat: u64, piece: usize) -> Self {
+        Self { seat, piece }
+    }
+}
+
+/// The state of a game in progress.
+///
+/// Only valid for the ruleset it was created from, which must already be verified.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Board {
+    /// Pieces on the board indexed by (row, column)
+    pieces: Array2<Option<BoardPiece>>,
+    /// The seat whose turn it is
+    current_seat: u64,
+    /// The number of turns that have been completed
+    turn_number: usize,
+    /// Piece indexes captured from each seat, indexed by seat
+    captured: Vec<Vec<usize>>,
+    /// Placement progress, `None` once pieces are moving
+    placement: Option<PlacementState>,
+}
+impl Board {
+    /// Creates an empty board with seat 0 to move.
+    /// Rulesets using `StartingPositions::Placement` start in the placement phase with `first_seat` to place.
+    pub fn new(ruleset: &Ruleset) -> Self {
+        let shape = (ruleset.board_type.rows() as usize, ruleset.board_type.columns() as usize);
+        let (current_seat, placement) = match &ruleset.starting_positions {
+            StartingPositions::Placement { first_seat, .. } => (*first_seat, Some(PlacementState::new(ruleset))),
+            _ => (0, None),
+        };
+        Self {
+            pieces: Array2::from_elem(shape, None),
+            current_seat,
+            turn_number: 0,
+            captured: vec![Vec::new(); ruleset.seats as usize],
+            placement,
+        }
+    }
+
+    /// Creates the starting board with every fixed starting position filled in.
+    /// Boards for `StartingPositions::Placement` are empty and in the placement phase.
+    pub fn initial(ruleset: &Ruleset) -> BoardResult<Self> {
+        let mut board = Self::new(ruleset);
+        if let Some(seat_positions) = ruleset.starting_positions.seat_positions(ruleset) {
+            for (seat, piece_positions) in seat_positions.into_iter().enumerate() {
+                for (piece, positions) in piece_positions.into_iter().enumerate() {
+                    for position in positions {
+                        board.place(ruleset, position, BoardPiece::new(seat as u64, piece))?;
+                    }
+                }
+            }
+        }
+        Ok(board)
+    }
+
+    pub fn rows(&self) -> usize {
+        self.pieces.nrows()
+    }
+    pub fn columns(&self) -> usize {
+        self.pieces.ncols()
+    }
+    pub fn seats(&self) -> u64 {
+        self.captured.len() as u64
+    }
+    pub fn in_bounds(&self, coordinate: Coordinate) -> bool {
+        coordinate.row >= 0 && coordinate.column >= 0 && (coordinate.row as usize) < self.rows() && (coordinate.column as usize) < self.columns()
+    }
+
+    /// Gets the piece at a coordinate, `None` if empty or out of bounds.
+    pub fn get(&self, coordinate: Coordinate) -> Option<BoardPiece> {
+        if self.in_bounds(coordinate) {
+            self.pieces[coordinate.to_tuple()]
+        } else {
+            None
+        }
+    }
+    /// All pieces on the board in row major order.
+    pub fn pieces(&self) -> impl Iterator<Item=(Coordinate, BoardPiece)> + '_ {
+        self.pieces.indexed_iter()
+            .filter_map(|((row, column), piece)| piece.map(|piece| (Coordinate::new(row as i16, column as i16), piece)))
+    }
+    /// All pieces on the board owned by `seat` in row major order.
+    pub fn seat_pieces(&self, seat: u64) -> impl Iterator<Item=(Coordinate, BoardPiece)> + '_ {
+        self.pieces().filter(move |(_, piece)| piece.seat == seat)
+    }
+
+    /// Places a piece on an empty, valid space.
+    pub fn place(&mut self, ruleset: &Ruleset, coordinate: Coordinate, piece: BoardPiece) -> BoardResult<()> {
+        if piece.seat >= self.seats() {
+            return Err(BoardError::SeatNotFound(piece.seat));
+        }
+        if ruleset.get_piece(piece.piece).is_none() {
+            return Err(BoardError::PieceIndexNotFound(piece.piece));
+        }
+        self.check_space(ruleset, coordinate)?;
+        if self.get(coordinate).is_some() {
+            return Err(BoardError::Occupied(coordinate));
+        }
+        self.pieces[coordinate.to_tuple()] = Some(piece);
+        Ok(())
+    }
+    /// Removes a piece from the board without counting it as captured.
+    pub fn remove(&mut self, coordinate: Coordinate) -> BoardResult<BoardPiece> {
+        match self.get(coordinate) {
+            None => Err(BoardError::NoPieceAt(coordinate)),
+            Some(piece) => {
+                self.pieces[coordinate.to_tuple()] = None;
+                Ok(piece)
+            }
+        }
+    }
+    /// Moves a piece to an empty, valid space.
+    pub fn move_piece(&mut self, ruleset: &Ruleset, from: Coordinate, to: Coordinate) -> BoardResult<BoardPiece> {
+        self.check_space(ruleset, to)?;
+        if self.get(to).is_some() {
+            return Err(BoardError::Occupied(to));
+        }
+        let piece = self.remove(from)?;
+        self.pieces[to.to_tuple()] = Some(piece);
+        Ok(piece)
+    }
+    /// Removes a piece from the board and records it as captured.
+    pub fn capture(&mut self, coordinate: Coordinate) -> BoardResult<BoardPiece> {
+        let piece = self.remove(coordinate)?;
+        self.captured[piece.seat as usize].push(piece.piece);
+        Ok(piece)
+    }
+    /// Puts a captured piece back, undoing the latest capture from its seat.
+    pub(crate) fn uncapture(&mut self, coordinate: Coordinate, piece: BoardPiece) {
+        self.captured[piece.seat as usize].pop();
+        self.set(coordinate, Some(piece));
+    }
+    /// Sets a space without any checks.
+    pub(crate) fn set(&mut self, coordinate: Coordinate, piece: Option<BoardPiece>) {
+        self.pieces[coordinate.to_tuple()] = piece;
+    }
+
+    pub fn current_seat(&self) -> u64 {
+        self.current_seat
+    }
+    pub fn turn_number(&self) -> usize {
+        self.turn_number
+    }
+    /// Piece indexes captured from `seat`.
+    pub fn captured(&self, seat: u64) -> &[usize] {
+        self.captured.get(seat as usize).map_or(&[], Vec::as_slice)
+    }
+    /// Passes the turn to the next seat.
+    pub fn end_turn(&mut self) {
+        self.current_seat = (self.current_seat + 1) % self.seats();
+        self.turn_number += 1;
+    }
+    pub(crate) fn set_current_seat(&mut self, seat: u64) {
+        self.current_seat = seat;
+    }
+    pub(crate) fn set_turn_number(&mut self, turn_number: usize) {
+        self.turn_number = turn_number;
+    }
+    pub(crate) fn set_captured(&mut self, seat: u64, captured: Vec<usize>) {
+        self.captured[seat as usize] = captured;
+    }
+    /// Placement progress, `None` once pieces are moving.
+    pub fn placement(&self) -> Option<&PlacementState> {
+        self.placement.as_ref()
+    }
+    pub(crate) fn placement_mut(&mut self) -> Option<&mut PlacementState> {
+        self.placement.as_mut()
+    }
+    pub(crate) fn set_placement(&mut self, placement: Option<PlacementState>) {
+        self.placement = placement;
+    }
+
+    fn check_space(&self, ruleset: &Ruleset, coordinate: Coordinate) -> BoardResult<()> {
+        if !self.in_bounds(coordinate) {
+            return Err(BoardError::OutOfBounds(coordinate));
+        }
+        match ruleset.board_type.get_space(coordinate) {
+            Space::Invalid => Err(BoardError::InvalidSpace(coordinate)),
+            Space::Normal | Space::Goal(_) => Ok(()),
+        }
+    }
+}
+
+pub type BoardResult<T> = Result<T, BoardError>;
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum BoardError {
+    OutOfBounds(Coordinate),
+    InvalidSpace(Coordinate),
+    Occupied(Coordinate),
+    NoPieceAt(Coordinate),
+    SeatNotFound(u64),
+    PieceIndexNotFound(usize),
+    NotSeatsTurn(u64),
+    InvalidJump {
+        from: Coordinate,
+        to: Coordinate,
+    },
+    /// A jump path needs a start and at least one landing, holds the path length
+    JumpPathTooShort(usize),
+}
+impl Display for BoardError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        <Self as Debug>::fmt(self, f)
+    }
+}
+impl Error for BoardError {}
+
+#[cfg(test)]
+mod test {
+    use crate::game::board::{Board, BoardError, BoardPiece};
+    use crate::game::Coordinate;
+    use crate::game::ruleset::standard::standard_rules;
+    use crate::game::ruleset::starting_positions::StartingPositions;
+
+    #[test]
+    fn place_respects_spaces() {
+        let ruleset = standard_rules();
+        let mut board = Board::new(&ruleset);
+        assert_eq!(board.rows(), 12);
+        assert_eq!(board.columns(), 10);
+
+        board.place(&ruleset, Coordinate::new(1, 1), BoardPiece::new(0, 0)).expect("Normal space");
+        board.place(&ruleset, Coordinate::new(0, 4), BoardPiece::new(0, 1)).expect("Goal space");
+        assert_eq!(board.place(&ruleset, Coordinate::new(0, 0), BoardPiece::new(0, 0)), Err(BoardError::InvalidSpace(Coordinate::new(0, 0))));
+        assert_eq!(board.place(&ruleset, Coordinate::new(1, 1), BoardPiece::new(1, 0)), Err(BoardError::Occupied(Coordinate::new(1, 1))));
+        assert_eq!(board.place(&ruleset, Coordinate::new(-1, 1), BoardPiece::new(1, 0)), Err(BoardError::OutOfBounds(Coordinate::new(-1, 1))));
+        assert_eq!(board.place(&ruleset, Coordinate::new(2, 2), BoardPiece::new(2, 0)), Err(BoardError::SeatNotFound(2)));
+        assert_eq!(board.place(&ruleset, Coordinate::new(2, 2), BoardPiece::new(1, 2)), Err(BoardError::PieceIndexNotFound(2)));
+        assert_eq!(board.pieces().count(), 2);
+    }
+
+    #[test]
+    fn initial_mirrored() {
+        let mut ruleset = standard_rules();
+        let board = Board::initial(&ruleset).unwrap();
+        assert_eq!(board.seat_pieces(0).count(), 4);
+        assert!(board.seat_pieces(1).all(|(_, piece)| piece.piece == 1));
+
+        ruleset.starting_positions = StartingPositions::MirroredFlipped(vec![vec![Coordinate::new(1, 2)], vec![Coordinate::new(2, 3)]]);
+        let board = Board::initial(&ruleset).unwrap();
+        assert_eq!(board.pieces().collect::<Vec<_>>(), vec![
+            (Coordinate::new(1, 2), BoardPiece::new(0, 0)),
+            (Coordinate::new(2, 3), BoardPiece::new(0, 1)),
+            (Coordinate::new(9, 3), BoardPiece::new(1, 1)),
+            (Coordinate::new(10, 2), BoardPiece::new(1, 0)),
+        ]);
+
+        ruleset.starting_positions = StartingPositions::MirroredRotated(vec![vec![Coordinate::new(1, 2)], vec![]]);
+        let board = Board::initial(&ruleset).unwrap();
+        assert_eq!(board.get(Coordinate::new(10, 7)), Some(BoardPiece::new(1, 0)));
+        assert_eq!(board.pieces().count(), 2);
+    }
+
+    #[test]
+    fn capture_and_turns() {
+        let ruleset = standard_rules();
+        let mut board = Board::new(&ruleset);
+        board.place(&ruleset, Coordinate::new(5, 5), BoardPiece::new(1, 1)).unwrap();
+        board.move_piece(&ruleset, Coordinate::new(5, 5), Coordinate::new(6, 5)).unwrap();
+        assert_eq!(board.get(Coordinate::new(5, 5)), None);
+        assert_eq!(board.capture(Coordinate::new(6, 5)), Ok(BoardPiece::new(1, 1)));
+        assert_eq!(board.captured(1), &[1]);
+        assert!(board.captured(0).is_empty());
+
+        board.end_turn();
+        assert_eq!(board.current_seat(), 1);
+        board.end_turn();
+        assert_eq!(board.current_seat(), 0);
+        assert_eq!(board.turn_number(), 2);
+    }
+}
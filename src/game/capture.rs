@@ -0,0 +1,248 @@
+use serde::{Deserialize, Serialize};
+
+use crate::game::board::{Board, BoardError, BoardPiece, BoardResult};
+use crate::game::Coordinate;
+use crate::game::movement::Move;
+use crate::game::ruleset::piece_definition::{CaptureRule, CaptureTarget, CaptureTimingRule};
+use crate::game::ruleset::Ruleset;
+
+/// A piece removed from the board by a capture.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct CapturedPiece {
+    /// Where the piece was when captured
+    pub position: Coordinate,
+    pub piece: BoardPiece,
+}
+
+/// Whether `attacker` can capture `target` using `rule`.
+pub fn can_capture(ruleset: &Ruleset, attacker: BoardPiece, target: BoardPiece, rule: CaptureRule) -> bool {
+    match ruleset.pieces[attacker.piece].capture_rules.get(&rule) {
+        None => false,
+        Some(CaptureTarget::EnemyOnly) => !ruleset.are_allied(attacker.seat, target.seat),
+        Some(CaptureTarget::OwnOnly) => attacker.seat == target.seat,
+        Some(CaptureTarget::All) => true,
+    }
+}
+
+/// Whether a legal move captures anything.
+pub fn is_capture(ruleset: &Ruleset, board: &Board, to_check: &Move) -> bool {
+    let (from, piece) = match to_check.from().and_then(|from| Some((from, board.get(from)?))) {
+        None => return false,
+        Some(found) => found,
+    };
+    match to_check {
+        Move::Step { to, .. } => board.get(*to).is_some(),
+        Move::Jump { path } => path.windows(2).any(|jump| {
+            let captures_over = match jump_over(jump[0], jump[1]) {
+                Ok(over) if over != from => matches!(board.get(over), Some(target) if can_capture(ruleset, piece, target, CaptureRule::JumpOver)),
+                _ => false,
+            };
+            captures_over || (jump[1] != from && board.get(jump[1]).is_some())
+        }),
+    }
+}
+
+/// Plays `to_apply` for the current seat and ends the turn, returning the captured pieces in capture order.
+///
+/// Jumped over pieces are removed after each jump for `CaptureTimingRule::AfterJump`, and only once the piece stops
+/// for `CaptureTimingRule::AfterTurn`, so a piece jumped twice is still only captured once.
+/// Pieces jumped on are always removed on landing as the jumping piece takes their space.
+///
+/// Only the shape of the move is checked, use the legal move list to check the move is allowed.
+/// The board is left untouched on error.
+pub fn apply_move(ruleset: &Ruleset, board: &mut Board, to_apply: &Move) -> BoardResult<Vec<CapturedPiece>> {
+    let mut next = board.clone();
+    let captured = apply_move_unchecked(ruleset, &mut next, to_apply)?;
+    *board = next;
+    Ok(captured)
+}
+fn apply_move_unchecked(ruleset: &Ruleset, board: &mut Board, to_apply: &Move) -> BoardResult<Vec<CapturedPiece>> {
+    if let Move::Jump { path } = to_apply {
+        if path.len() < 2 {
+            return Err(BoardError::JumpPathTooShort(path.len()));
+        }
+    }
+    let (from, to) = match (to_apply.from(), to_apply.to()) {
+        (Some(from), Some(to)) => (from, to),
+        _ => return Err(BoardError::JumpPathTooShort(0)),
+    };
+    let piece = match board.get(from) {
+        None => return Err(BoardError::NoPieceAt(from)),
+        Some(piece) => piece,
+    };
+    if piece.seat != board.current_seat() {
+        return Err(BoardError::NotSeatsTurn(piece.seat));
+    }
+
+    let mut captured = Vec::new();
+    match to_apply {
+        Move::Step { to, .. } => {
+            capture_on(ruleset, board, piece, *to, CaptureRule::Move, &mut captured)?;
+            board.move_piece(ruleset, from, *to)?;
+        }
+        Move::Jump { path } => {
+            let mut after_turn = Vec::new();
+            board.remove(from)?;
+            for jump in path.windows(2) {
+                let over = jump_over(jump[0], jump[1])?;
+                if let Some(target) = board.get(over) {
+                    if can_capture(ruleset, piece, target, CaptureRule::JumpOver) {
+                        match ruleset.pieces[piece.piece].capture_timing_rule {
+                            CaptureTimingRule::AfterJump => captured.push(CapturedPiece { position: over, piece: board.capture(over)? }),
+                            CaptureTimingRule::AfterTurn => if !after_turn.contains(&over) {
+                                after_turn.push(over);
+                            },
+                        }
+                    }
+                } else {
+                    return Err(BoardError::InvalidJump { from: jump[0], to: jump[1] });
+                }
+                after_turn.retain(|&position| position != jump[1]);
+                capture_on(ruleset, board, piece, jump[1], CaptureRule::JumpOn, &mut captured)?;
+            }
+            board.place(ruleset, to, piece)?;
+            for position in after_turn {
+                captured.push(CapturedPiece { position, piece: board.capture(position)? });
+            }
+        }
+    }
+    board.end_turn();
+    Ok(captured)
+}
+
+/// The space jumped over between two landing spots.
+fn jump_over(from: Coordinate, to: Coordinate) -> BoardResult<Coordinate> {
+    let difference = to - from;
+    let valid = |delta: i16| delta == 0 || delta.abs() == 2;
+    if difference == Coordinate::new(0, 0) || !valid(difference.row) || !valid(difference.column) {
+        return Err(BoardError::InvalidJump { from, to });
+    }
+    Ok(Coordinate::new(from.row + difference.row / 2, from.column + difference.column / 2))
+}
+
+/// Captures the piece at `position` if there is one, erroring if `rule` does not allow it.
+fn capture_on(ruleset: &Ruleset, board: &mut Board, attacker: BoardPiece, position: Coordinate, rule: CaptureRule, captured: &mut Vec<CapturedPiece>) -> BoardResult<()> {
+    if let Some(target) = board.get(position) {
+        if !can_capture(ruleset, attacker, target, rule) {
+            return Err(BoardError::Occupied(position));
+        }
+        captured.push(CapturedPiece { position, piece: board.capture(position)? });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::game::board::{Board, BoardError, BoardPiece};
+    use crate::game::capture::{apply_move, CapturedPiece};
+    use crate::game::Coordinate;
+    use crate::game::jump::jump_paths;
+    use crate::game::movement::{Move, step_moves};
+    use crate::game::ruleset::piece_definition::{CaptureRule, CaptureTarget, CaptureTimingRule, JumpRule};
+    use crate::game::ruleset::Ruleset;
+    use crate::game::ruleset::standard::standard_rules;
+
+    const BIG: usize = 0;
+    const LITTLE: usize = 1;
+
+    fn board_with(ruleset: &Ruleset, pieces: &[(i16, i16, u64, usize)]) -> Board {
+        let mut board = Board::new(ruleset);
+        for &(row, column, seat, piece) in pieces {
+            board.place(ruleset, Coordinate::new(row, column), BoardPiece::new(seat, piece)).unwrap();
+        }
+        board
+    }
+    fn jump(path: &[(i16, i16)]) -> Move {
+        Move::Jump { path: path.iter().map(|&(row, column)| Coordinate::new(row, column)).collect() }
+    }
+
+    #[test]
+    fn jump_over_enemy_only() {
+        let ruleset = standard_rules();
+        let mut board = board_with(&ruleset, &[(5, 5, 0, BIG), (4, 5, 1, LITTLE), (2, 5, 0, LITTLE)]);
+        let captured = apply_move(&ruleset, &mut board, &jump(&[(5, 5), (3, 5), (1, 5)])).unwrap();
+        assert_eq!(captured, vec![CapturedPiece { position: Coordinate::new(4, 5), piece: BoardPiece::new(1, LITTLE) }]);
+        assert_eq!(board.get(Coordinate::new(1, 5)), Some(BoardPiece::new(0, BIG)));
+        assert_eq!(board.get(Coordinate::new(2, 5)), Some(BoardPiece::new(0, LITTLE)));
+        assert_eq!(board.captured(1), &[LITTLE]);
+        assert_eq!(board.current_seat(), 1);
+    }
+
+    #[test]
+    fn short_jump_path() {
+        let ruleset = standard_rules();
+        let mut board = board_with(&ruleset, &[(5, 5, 0, BIG)]);
+        let before = board.clone();
+        assert!(matches!(apply_move(&ruleset, &mut board, &jump(&[])), Err(BoardError::JumpPathTooShort(0))));
+        assert!(matches!(apply_move(&ruleset, &mut board, &jump(&[(5, 5)])), Err(BoardError::JumpPathTooShort(1))));
+        assert_eq!(board, before);
+    }
+
+    #[test]
+    fn after_turn_captures_once() {
+        let mut ruleset = standard_rules();
+        ruleset.pieces[BIG].jump_rule = JumpRule::Open;
+        let mut board = board_with(&ruleset, &[(5, 5, 0, BIG), (4, 5, 1, LITTLE)]);
+        assert!(jump_paths(&ruleset, &board, Coordinate::new(5, 5)).contains(&vec![Coordinate::new(5, 5), Coordinate::new(3, 5), Coordinate::new(5, 5)]));
+        let captured = apply_move(&ruleset, &mut board, &jump(&[(5, 5), (3, 5), (5, 5)])).unwrap();
+        assert_eq!(captured.len(), 1);
+        assert_eq!(board.pieces().count(), 1);
+    }
+
+    #[test]
+    fn after_jump_removes_immediately() {
+        let mut ruleset = standard_rules();
+        ruleset.pieces[BIG].jump_rule = JumpRule::Open;
+        ruleset.pieces[BIG].capture_timing_rule = CaptureTimingRule::AfterJump;
+        let board = board_with(&ruleset, &[(5, 5, 0, BIG), (4, 5, 1, LITTLE), (3, 6, 0, LITTLE)]);
+        let paths = jump_paths(&ruleset, &board, Coordinate::new(5, 5));
+        // The enemy is gone after the first jump so it cannot be jumped back over
+        assert!(!paths.contains(&vec![Coordinate::new(5, 5), Coordinate::new(3, 5), Coordinate::new(5, 5)]));
+        // Own pieces are not captured so they can be jumped repeatedly
+        assert!(paths.contains(&vec![Coordinate::new(5, 5), Coordinate::new(3, 5), Coordinate::new(3, 7), Coordinate::new(3, 5)]));
+
+        let mut board = board;
+        let captured = apply_move(&ruleset, &mut board, &jump(&[(5, 5), (3, 5), (3, 7)])).unwrap();
+        assert_eq!(captured, vec![CapturedPiece { position: Coordinate::new(4, 5), piece: BoardPiece::new(1, LITTLE) }]);
+        assert_eq!(board.get(Coordinate::new(3, 6)), Some(BoardPiece::new(0, LITTLE)));
+    }
+
+    #[test]
+    fn capture_targets() {
+        let mut ruleset = standard_rules();
+        ruleset.pieces[BIG].capture_rules.insert(CaptureRule::JumpOver, CaptureTarget::OwnOnly);
+        let mut board = board_with(&ruleset, &[(5, 5, 0, BIG), (4, 5, 1, LITTLE), (3, 6, 0, LITTLE)]);
+        let captured = apply_move(&ruleset, &mut board, &jump(&[(5, 5), (3, 5), (3, 7)])).unwrap();
+        assert_eq!(captured, vec![CapturedPiece { position: Coordinate::new(3, 6), piece: BoardPiece::new(0, LITTLE) }]);
+
+        ruleset.pieces[BIG].capture_rules.insert(CaptureRule::JumpOver, CaptureTarget::All);
+        let mut board = board_with(&ruleset, &[(5, 5, 0, BIG), (4, 5, 1, LITTLE), (3, 6, 0, LITTLE)]);
+        assert_eq!(apply_move(&ruleset, &mut board, &jump(&[(5, 5), (3, 5), (3, 7)])).unwrap().len(), 2);
+
+        // Allies are not enemies
+        ruleset.pieces[BIG].capture_rules.insert(CaptureRule::JumpOver, CaptureTarget::EnemyOnly);
+        ruleset.allies = vec![vec![0, 1].into_iter().collect()];
+        let mut board = board_with(&ruleset, &[(5, 5, 0, BIG), (4, 5, 1, LITTLE)]);
+        assert!(apply_move(&ruleset, &mut board, &jump(&[(5, 5), (3, 5)])).unwrap().is_empty());
+    }
+
+    #[test]
+    fn jump_on_and_move_captures() {
+        let mut ruleset = standard_rules();
+        ruleset.pieces[LITTLE].capture_rules.insert(CaptureRule::JumpOn, CaptureTarget::EnemyOnly);
+        ruleset.pieces[LITTLE].capture_rules.insert(CaptureRule::Move, CaptureTarget::EnemyOnly);
+        let mut board = board_with(&ruleset, &[(5, 5, 0, LITTLE), (4, 5, 1, LITTLE), (3, 5, 1, BIG), (6, 6, 0, BIG)]);
+        assert!(jump_paths(&ruleset, &board, Coordinate::new(5, 5)).contains(&vec![Coordinate::new(5, 5), Coordinate::new(3, 5)]));
+        let steps = step_moves(&ruleset, &board, Coordinate::new(5, 5));
+        assert!(steps.contains(&Move::Step { from: Coordinate::new(5, 5), to: Coordinate::new(4, 5) }));
+        assert!(!steps.contains(&Move::Step { from: Coordinate::new(5, 5), to: Coordinate::new(6, 6) }));
+
+        let captured = apply_move(&ruleset, &mut board, &jump(&[(5, 5), (3, 5)])).unwrap();
+        // Jumped on pieces are removed on landing, jumped over ones at the end of the turn
+        assert_eq!(captured, vec![
+            CapturedPiece { position: Coordinate::new(3, 5), piece: BoardPiece::new(1, BIG) },
+            CapturedPiece { position: Coordinate::new(4, 5), piece: BoardPiece::new(1, LITTLE) },
+        ]);
+        assert_eq!(board.get(Coordinate::new(3, 5)), Some(BoardPiece::new(0, LITTLE)));
+    }
+}
@@ -1,11 +1,12 @@
 use std::ops::{Add, AddAssign, Mul, Sub, SubAssign};
 use std::ops::MulAssign;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::game::ruleset::board_type::BoardType;
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize, JsonSchema)]
 pub struct Coordinate {
     pub row: i16,
     pub column: i16,
@@ -22,7 +23,7 @@ impl Add for Coordinate {
     type Output = Self;
 
     fn add(self, rhs: Self) -> Self::Output {
-        Self::new(self.row + rhs.row, self.column)
+        Self::new(self.row + rhs.row, self.column + rhs.column)
     }
 }
 impl AddAssign for Coordinate {
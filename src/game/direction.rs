@@ -2,12 +2,15 @@ use std::collections::HashSet;
 use std::hash::Hash;
 
 use bitflags::bitflags;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::game::coordinate::Coordinate;
 
 bitflags! {
-    #[derive(Serialize, Deserialize)]
+    /// A set of directions, serialized as `{"bits": <sum>}` where the sum adds NORTH 1, SOUTH 2, EAST 4, WEST 8,
+    /// NORTH_WEST 16, NORTH_EAST 32, SOUTH_WEST 64, and SOUTH_EAST 128 for each direction in the set.
+    #[derive(Serialize, Deserialize, JsonSchema)]
     pub struct Directions: u8 {
         const NORTH         = 0b00000001;
         const SOUTH         = 0b00000010;
@@ -62,15 +65,16 @@ pub enum Direction {
     SouthEast,
 }
 impl Direction {
+    /// The (row, column) offset of a single step, north is towards row 0.
     pub fn offset(&self) -> Coordinate {
         match self {
-            Direction::North => Coordinate::new(0, -1),
-            Direction::South => Coordinate::new(0, 1),
-            Direction::East => Coordinate::new(1, 0),
-            Direction::West => Coordinate::new(-1, 0),
+            Direction::North => Coordinate::new(-1, 0),
+            Direction::South => Coordinate::new(1, 0),
+            Direction::East => Coordinate::new(0, 1),
+            Direction::West => Coordinate::new(0, -1),
             Direction::NorthWest => Coordinate::new(-1, -1),
-            Direction::NorthEast => Coordinate::new(1, -1),
-            Direction::SouthWest => Coordinate::new(-1, 1),
+            Direction::NorthEast => Coordinate::new(-1, 1),
+            Direction::SouthWest => Coordinate::new(1, -1),
             Direction::SouthEast => Coordinate::new(1, 1),
         }
     }
@@ -0,0 +1,248 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::game::board::{Board, BoardError, BoardPiece};
+use crate::game::Coordinate;
+use crate::game::placement::{check_piece_limits, PlacementError, PlacementResult};
+use crate::game::ruleset::board_type::space::Space;
+use crate::game::ruleset::Ruleset;
+use crate::game::ruleset::starting_positions::alteration_type::AlternationType;
+use crate::game::ruleset::starting_positions::StartingPositions;
+
+/// A single piece of a hidden placement.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct HiddenPiece {
+    pub coordinate: Coordinate,
+    /// Index into `Ruleset::pieces`
+    pub piece: usize,
+}
+
+/// What a seat has submitted for `AlternationType::Hidden`.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum HiddenSubmission {
+    /// Nothing submitted yet.
+    Waiting,
+    /// Hash from `commitment`, the placement must be revealed once every seat has submitted.
+    Committed(Vec<u8>),
+    /// The full placement is locked in.
+    Locked(Vec<HiddenPiece>),
+    /// Another seat's locked placement, shown by `hidden_view`.
+    Withheld,
+}
+
+/// The commitment for a placement, sha256 of `salt` followed by the placement serialized with `serde_json`.
+pub fn commitment(pieces: &[HiddenPiece], salt: &[u8]) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.update(salt);
+    hasher.update(serde_json::to_vec(pieces).expect("Placement serialization cannot fail"));
+    hasher.finalize().to_vec()
+}
+
+/// Locks in a commitment for `seat` without showing the placement.
+pub fn commit_hidden_placement(ruleset: &Ruleset, board: &mut Board, seat: u64, commitment: Vec<u8>) -> PlacementResult<()> {
+    check_hidden(ruleset)?;
+    let submission = submission_mut(board, seat)?;
+    if *submission != HiddenSubmission::Waiting {
+        return Err(PlacementError::AlreadySubmitted(seat));
+    }
+    *submission = HiddenSubmission::Committed(commitment);
+    Ok(())
+}
+
+/// Locks in the full placement for `seat`.
+///
+/// Seats that committed must pass their `salt` and can only reveal once every seat has submitted.
+/// Once every seat is locked in all placements are put on the board at once, returning the collisions.
+/// Pieces placed on the same space by different seats are all discarded.
+pub fn lock_hidden_placement(ruleset: &Ruleset, board: &mut Board, seat: u64, pieces: Vec<HiddenPiece>, salt: Option<&[u8]>) -> PlacementResult<Option<Vec<Coordinate>>> {
+    check_hidden(ruleset)?;
+    check_hidden_placement(ruleset, board, seat, &pieces)?;
+    let state = board.placement().ok_or(PlacementError::NotPlacementPhase)?;
+    match state.hidden().get(seat as usize).ok_or(BoardError::SeatNotFound(seat))? {
+        HiddenSubmission::Waiting => {}
+        HiddenSubmission::Committed(hash) => {
+            if state.hidden().contains(&HiddenSubmission::Waiting) {
+                return Err(PlacementError::WaitingForCommitments);
+            }
+            if *hash != commitment(&pieces, salt.unwrap_or_default()) {
+                return Err(PlacementError::CommitmentMismatch);
+            }
+        }
+        HiddenSubmission::Locked(_) | HiddenSubmission::Withheld => return Err(PlacementError::AlreadySubmitted(seat)),
+    }
+    *submission_mut(board, seat)? = HiddenSubmission::Locked(pieces);
+
+    let state = board.placement().ok_or(PlacementError::NotPlacementPhase)?;
+    if state.hidden().iter().all(|submission| matches!(submission, HiddenSubmission::Locked(_))) {
+        Ok(Some(reveal(ruleset, board)?))
+    } else {
+        Ok(None)
+    }
+}
+
+/// The board as `seat` is allowed to see it, other seats' locked placements are withheld.
+pub fn hidden_view(board: &Board, seat: u64) -> Board {
+    let mut out = board.clone();
+    if let Some(state) = out.placement_mut() {
+        for (other, submission) in state.hidden_mut().iter_mut().enumerate() {
+            if other as u64 != seat {
+                if let HiddenSubmission::Locked(_) = submission {
+                    *submission = HiddenSubmission::Withheld;
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Checks a full placement against the seat's area and the piece limits.
+pub fn check_hidden_placement(ruleset: &Ruleset, board: &Board, seat: u64, pieces: &[HiddenPiece]) -> PlacementResult<()> {
+    let placement_area = match &ruleset.starting_positions {
+        StartingPositions::Placement { placement_area, .. } => placement_area,
+        _ => return Err(PlacementError::NotPlacementPhase),
+    };
+    if seat >= ruleset.seats {
+        return Err(BoardError::SeatNotFound(seat).into());
+    }
+    let mut state = board.placement().ok_or(PlacementError::NotPlacementPhase)?.clone();
+    let area = placement_area.seat_area(ruleset, seat);
+    let mut used = HashSet::new();
+    for &HiddenPiece { coordinate, piece } in pieces {
+        if ruleset.get_piece(piece).is_none() {
+            return Err(BoardError::PieceIndexNotFound(piece).into());
+        }
+        if !area.contains(&coordinate) {
+            return Err(PlacementError::OutsideArea(coordinate));
+        }
+        if ruleset.board_type.get_space(coordinate) == Space::Invalid {
+            return Err(BoardError::InvalidSpace(coordinate).into());
+        }
+        if !used.insert(coordinate) || board.get(coordinate).is_some() {
+            return Err(BoardError::Occupied(coordinate).into());
+        }
+        check_piece_limits(ruleset, &state, seat, piece)?;
+        state.add_placed(seat, piece);
+    }
+    Ok(())
+}
+
+fn check_hidden(ruleset: &Ruleset) -> PlacementResult<()> {
+    match &ruleset.starting_positions {
+        StartingPositions::Placement { alternation_type: AlternationType::Hidden, .. } => Ok(()),
+        _ => Err(PlacementError::NotHiddenPlacement),
+    }
+}
+
+fn submission_mut(board: &mut Board, seat: u64) -> PlacementResult<&mut HiddenSubmission> {
+    let state = board.placement_mut().ok_or(PlacementError::NotPlacementPhase)?;
+    state.hidden_mut().get_mut(seat as usize).ok_or(PlacementError::BoardError(BoardError::SeatNotFound(seat)))
+}
+
+/// Puts every locked placement on the board and starts the movement phase.
+fn reveal(ruleset: &Ruleset, board: &mut Board) -> PlacementResult<Vec<Coordinate>> {
+    let first_seat = match &ruleset.starting_positions {
+        StartingPositions::Placement { first_seat, .. } => *first_seat,
+        _ => return Err(PlacementError::NotPlacementPhase),
+    };
+    let state = board.placement().ok_or(PlacementError::NotPlacementPhase)?.clone();
+    let mut claims: HashMap<Coordinate, usize> = HashMap::new();
+    for submission in state.hidden() {
+        if let HiddenSubmission::Locked(pieces) = submission {
+            for hidden_piece in pieces {
+                *claims.entry(hidden_piece.coordinate).or_default() += 1;
+            }
+        }
+    }
+    for (seat, submission) in state.hidden().iter().enumerate() {
+        if let HiddenSubmission::Locked(pieces) = submission {
+            for hidden_piece in pieces {
+                if claims[&hidden_piece.coordinate] == 1 {
+                    board.place(ruleset, hidden_piece.coordinate, BoardPiece::new(seat as u64, hidden_piece.piece))?;
+                }
+            }
+        }
+    }
+    let mut collisions: Vec<_> = claims.into_iter()
+        .filter(|&(_, count)| count > 1)
+        .map(|(coordinate, _)| coordinate)
+        .collect();
+    collisions.sort_by_key(|coordinate| (coordinate.row, coordinate.column));
+    board.set_placement(None);
+    board.set_current_seat(first_seat);
+    Ok(collisions)
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use crate::game::board::{Board, BoardError, BoardPiece};
+    use crate::game::Coordinate;
+    use crate::game::hidden_placement::{check_hidden_placement, commit_hidden_placement, commitment, hidden_view, HiddenPiece, HiddenSubmission, lock_hidden_placement};
+    use crate::game::placement::PlacementError;
+    use crate::game::ruleset::Ruleset;
+    use crate::game::ruleset::standard::standard_rules;
+    use crate::game::ruleset::starting_positions::alteration_type::AlternationType;
+    use crate::game::ruleset::starting_positions::piece_limit::PieceLimit;
+    use crate::game::ruleset::starting_positions::placement_area::PlacementArea;
+    use crate::game::ruleset::starting_positions::StartingPositions;
+    use crate::game::state::{Action, GameState, StateError};
+
+    /// Both seats may place anywhere on rows 5 and 6.
+    fn hidden_rules() -> Ruleset {
+        let mut ruleset = standard_rules();
+        let area: HashSet<_> = (0..10).flat_map(|column| vec![Coordinate::new(5, column), Coordinate::new(6, column)]).collect();
+        ruleset.starting_positions = StartingPositions::Placement {
+            first_seat: 0,
+            alternation_type: AlternationType::Hidden,
+            placement_area: PlacementArea::NonMirrored(vec![area.clone(), area]),
+            piece_limits: vec![PieceLimit::TotalLimit { limit: 2 }].into_iter().collect(),
+        };
+        ruleset
+    }
+    fn piece(row: i16, column: i16, piece: usize) -> HiddenPiece {
+        HiddenPiece { coordinate: Coordinate::new(row, column), piece }
+    }
+
+    #[test]
+    fn commit_and_reveal() {
+        let ruleset = hidden_rules();
+        let mut board = Board::new(&ruleset);
+        let seat_0 = vec![piece(5, 0, 0), piece(5, 1, 1)];
+        let seat_1 = vec![piece(6, 0, 0), piece(5, 1, 0)];
+
+        commit_hidden_placement(&ruleset, &mut board, 0, commitment(&seat_0, b"salt")).unwrap();
+        assert_eq!(lock_hidden_placement(&ruleset, &mut board, 0, seat_0.clone(), Some(b"salt")), Err(PlacementError::WaitingForCommitments));
+        assert_eq!(lock_hidden_placement(&ruleset, &mut board, 1, vec![piece(5, 2, 0), piece(5, 3, 0), piece(5, 4, 0)], None), Err(PlacementError::TotalLimitReached));
+        assert_eq!(lock_hidden_placement(&ruleset, &mut board, 1, seat_1.clone(), None), Ok(None));
+
+        // Seat 0 cannot see seat 1's pieces, but seat 1 can
+        assert_eq!(hidden_view(&board, 0).placement().unwrap().hidden()[1], HiddenSubmission::Withheld);
+        assert_eq!(hidden_view(&board, 1).placement().unwrap().hidden()[1], HiddenSubmission::Locked(seat_1));
+        assert!(board.pieces().next().is_none());
+
+        assert_eq!(lock_hidden_placement(&ruleset, &mut board, 0, seat_0.clone(), Some(b"pepper")), Err(PlacementError::CommitmentMismatch));
+        let collisions = lock_hidden_placement(&ruleset, &mut board, 0, seat_0, Some(b"salt")).unwrap();
+        assert_eq!(collisions, Some(vec![Coordinate::new(5, 1)]));
+        assert!(board.placement().is_none());
+        assert_eq!(board.get(Coordinate::new(5, 0)), Some(BoardPiece::new(0, 0)));
+        assert_eq!(board.get(Coordinate::new(6, 0)), Some(BoardPiece::new(1, 0)));
+        assert_eq!(board.get(Coordinate::new(5, 1)), None);
+    }
+
+    #[test]
+    fn seat_out_of_range() {
+        let ruleset = hidden_rules();
+        let mut board = Board::new(&ruleset);
+        let not_found = PlacementError::BoardError(BoardError::SeatNotFound(9));
+        assert_eq!(lock_hidden_placement(&ruleset, &mut board, 9, vec![], None), Err(not_found.clone()));
+        assert_eq!(check_hidden_placement(&ruleset, &board, 9, &[piece(5, 0, 0)]), Err(not_found.clone()));
+        assert_eq!(commit_hidden_placement(&ruleset, &mut board, 9, vec![]), Err(not_found));
+
+        let mut state = GameState::new(&ruleset).unwrap();
+        let result = state.apply(&ruleset, Action::HiddenPlacement { seat: 9, pieces: vec![] });
+        assert!(matches!(result, Err(StateError::PlacementError(PlacementError::BoardError(BoardError::SeatNotFound(9))))));
+    }
+}
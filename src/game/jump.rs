@@ -0,0 +1,387 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::board::{Board, BoardPiece};
+use crate::game::capture::{can_capture, CapturedPiece};
+use crate::game::Coordinate;
+use crate::game::direction::Direction;
+use crate::game::movement::Move;
+use crate::game::ruleset::board_type::space::Space;
+use crate::game::ruleset::piece_definition::{CaptureRule, CaptureTimingRule, GoalMovementRule, JumpLimit, JumpRule, PieceDefinition};
+use crate::game::ruleset::Ruleset;
+
+/// A node in the tree of jump sequences for a piece.
+///
+/// The root is the starting position, every other node is a landing spot.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct JumpTree {
+    /// Where the piece is at this point of the sequence
+    pub position: Coordinate,
+    /// Whether the sequence is allowed to stop here
+    pub can_end: bool,
+    /// Jumps that can continue from here
+    pub children: Vec<JumpTree>,
+}
+impl JumpTree {
+    /// Every path that can be submitted as a jump, each including the starting position.
+    pub fn paths(&self) -> Vec<Vec<Coordinate>> {
+        let mut out = Vec::new();
+        let mut path = vec![self.position];
+        for child in &self.children {
+            child.collect_paths(&mut path, &mut out);
+        }
+        out
+    }
+    /// Whether `path` is a complete sequence from this tree, including the starting position.
+    pub fn contains_path(&self, path: &[Coordinate]) -> bool {
+        match path.split_first() {
+            Some((&first, rest)) if first == self.position => {
+                if rest.is_empty() {
+                    self.can_end
+                } else {
+                    self.children.iter().any(|child| child.contains_path(rest))
+                }
+            }
+            _ => false,
+        }
+    }
+
+    fn collect_paths(&self, path: &mut Vec<Coordinate>, out: &mut Vec<Vec<Coordinate>>) {
+        path.push(self.position);
+        if self.can_end {
+            out.push(path.clone());
+        }
+        for child in &self.children {
+            child.collect_paths(path, out);
+        }
+        path.pop();
+    }
+}
+
+/// A jump path and every piece it captures, see `jump_outcomes`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct JumpOutcome {
+    pub path: Vec<Coordinate>,
+    /// Captured pieces ordered by position
+    pub captured: Vec<CapturedPiece>,
+}
+
+/// Builds the tree of every jump sequence for the piece at `from`.
+///
+/// A jump goes over an adjacent piece onto the empty space directly behind it,
+/// or onto a piece it can capture with `CaptureRule::JumpOn`.
+/// No jump (from, to) may be repeated within a sequence, `JumpRule` restricts it further.
+/// Pieces captured with `CaptureTimingRule::AfterJump` are gone for the rest of the sequence.
+pub fn jump_tree(ruleset: &Ruleset, board: &Board, from: Coordinate) -> JumpTree {
+    JumpTree {
+        position: from,
+        can_end: false,
+        children: match JumpSearch::new(ruleset, board, from) {
+            None => Vec::new(),
+            Some(mut search) => search.children(from),
+        },
+    }
+}
+/// One jump path for the piece at `from` for each landing spot and set of captured pieces, see `jump_tree`.
+///
+/// Paths that land on the same space with the same captures leave the same board. A partial sequence is skipped when
+/// one that reached the same space with the same captures had only used some of its jumps, as every way on from it
+/// was already searched. That keeps this fast when the full tree is far too large to list.
+pub fn jump_outcomes(ruleset: &Ruleset, board: &Board, from: Coordinate) -> Vec<JumpOutcome> {
+    let mut outcomes = Outcomes::default();
+    if let Some(mut search) = JumpSearch::new(ruleset, board, from) {
+        search.outcomes(from, &mut outcomes);
+    }
+    outcomes.found
+}
+/// Every jump path for the piece at `from`, each including the starting position.
+pub fn jump_paths(ruleset: &Ruleset, board: &Board, from: Coordinate) -> Vec<Vec<Coordinate>> {
+    jump_tree(ruleset, board, from).paths()
+}
+/// Every jump move for the piece at `from`.
+pub fn jump_moves(ruleset: &Ruleset, board: &Board, from: Coordinate) -> Vec<Move> {
+    jump_paths(ruleset, board, from).into_iter()
+        .map(|path| Move::Jump { path })
+        .collect()
+}
+
+struct JumpSearch<'a> {
+    ruleset: &'a Ruleset,
+    board: &'a Board,
+    piece: BoardPiece,
+    definition: &'a PieceDefinition,
+    start: Coordinate,
+    only_to_goal: bool,
+    limit: Option<usize>,
+    directions: Vec<Direction>,
+    /// Positions in the current sequence, including the start
+    visited: Vec<Coordinate>,
+    /// Jumps already made in the current sequence
+    jumps: Vec<(Coordinate, Coordinate)>,
+    /// Pieces already removed by captures in the current sequence
+    removed: Vec<Coordinate>,
+    /// Pieces captured so far in the current sequence
+    captured: Vec<Coordinate>,
+}
+
+/// A partial sequence already searched by `JumpSearch::outcomes`.
+struct Explored {
+    /// Jumps it used as bits
+    jumps: Vec<u64>,
+    /// Spaces it visited, only kept for `JumpRule::NoBacktracking`
+    visited: Vec<Coordinate>,
+}
+
+/// Results of `JumpSearch::outcomes`.
+#[derive(Default)]
+struct Outcomes {
+    /// Bit index of every jump seen
+    indices: HashMap<(Coordinate, Coordinate), usize>,
+    /// Jumps used by the current sequence as bits
+    used: Vec<u64>,
+    /// Every searched sequence by position, captured pieces, and removed pieces
+    explored: HashMap<(Coordinate, Vec<Coordinate>, Vec<Coordinate>), Vec<Explored>>,
+    /// Landing spots and captured pieces already found
+    ends: HashSet<(Coordinate, Vec<Coordinate>)>,
+    found: Vec<JumpOutcome>,
+}
+impl Outcomes {
+    /// Flips whether the current sequence used the jump from `from` to `to`.
+    fn toggle(&mut self, from: Coordinate, to: Coordinate) {
+        let next = self.indices.len();
+        let index = *self.indices.entry((from, to)).or_insert(next);
+        if self.used.len() <= index / 64 {
+            self.used.resize(index / 64 + 1, 0);
+        }
+        self.used[index / 64] ^= 1 << (index % 64);
+    }
+    /// Whether every jump in `jumps` was used by the current sequence.
+    fn used_all(&self, jumps: &[u64]) -> bool {
+        jumps.iter().enumerate().all(|(word, bits)| bits & !self.used.get(word).copied().unwrap_or(0) == 0)
+    }
+}
+impl<'a> JumpSearch<'a> {
+    /// A search for the piece at `from`, `None` if it cannot jump.
+    fn new(ruleset: &'a Ruleset, board: &'a Board, from: Coordinate) -> Option<Self> {
+        let piece = board.get(from)?;
+        let definition = &ruleset.pieces[piece.piece];
+        let goal_move_rule = match ruleset.board_type.get_space(from) {
+            Space::Goal(_) => definition.goal_move_rule,
+            Space::Normal | Space::Invalid => GoalMovementRule::Free,
+        };
+        let (limit, directions) = match (goal_move_rule, definition.jump_limit) {
+            (GoalMovementRule::Locked, _) | (_, JumpLimit::Cannot) => return None,
+            (_, JumpLimit::Unlimited { directions }) => (None, directions),
+            (_, JumpLimit::Limited { limit, directions }) => (Some(limit), directions),
+        };
+        Some(Self {
+            ruleset,
+            board,
+            piece,
+            definition,
+            start: from,
+            only_to_goal: goal_move_rule == GoalMovementRule::OnlyToGoal,
+            limit,
+            directions: directions.into(),
+            visited: vec![from],
+            jumps: Vec::new(),
+            removed: Vec::new(),
+            captured: Vec::new(),
+        })
+    }
+
+    fn children(&mut self, position: Coordinate) -> Vec<JumpTree> {
+        let mut out = Vec::new();
+        for (over, to) in self.jumps_from(position) {
+            let removed = self.enter(position, over, to);
+            let node = JumpTree {
+                position: to,
+                can_end: self.can_end(to),
+                children: self.children(to),
+            };
+            self.leave(removed);
+
+            if node.can_end || !node.children.is_empty() {
+                out.push(node);
+            }
+        }
+        out
+    }
+    /// Walks the same sequences as `children`, adding the first path found for each landing spot and captures.
+    fn outcomes(&mut self, position: Coordinate, outcomes: &mut Outcomes) {
+        let mut captured = self.captured.clone();
+        captured.sort_by_key(|position| (position.row, position.column));
+        let mut removed = self.removed.clone();
+        removed.sort_by_key(|position| (position.row, position.column));
+        let no_backtracking = self.definition.jump_rule == JumpRule::NoBacktracking;
+        let key = (position, captured.clone(), removed);
+        // An earlier sequence that used only some of these jumps already searched every way on from here
+        let dominated = outcomes.explored.get(&key).map_or(false, |explored| explored.iter().any(|explored| {
+            outcomes.used_all(&explored.jumps) && explored.visited.iter().all(|position| self.visited.contains(position))
+        }));
+        if dominated {
+            return;
+        }
+        let visited = if no_backtracking { self.visited.clone() } else { Vec::new() };
+        outcomes.explored.entry(key).or_default().push(Explored { jumps: outcomes.used.clone(), visited });
+
+        if self.visited.len() > 1 && self.can_end(position) && outcomes.ends.insert((position, captured.clone())) {
+            outcomes.found.push(JumpOutcome {
+                path: self.visited.clone(),
+                captured: captured.into_iter()
+                    .filter_map(|position| Some(CapturedPiece { position, piece: self.board.get(position)? }))
+                    .collect(),
+            });
+        }
+        for (over, to) in self.jumps_from(position) {
+            let entered = self.enter(position, over, to);
+            outcomes.toggle(position, to);
+            self.outcomes(to, outcomes);
+            outcomes.toggle(position, to);
+            self.leave(entered);
+        }
+    }
+    /// Every jump (over, to) that can continue the sequence from `position`.
+    fn jumps_from(&self, position: Coordinate) -> Vec<(Coordinate, Coordinate)> {
+        if let Some(limit) = self.limit {
+            if self.visited.len() > limit {
+                return Vec::new();
+            }
+        }
+        self.directions.iter()
+            .map(|direction| (position + direction.offset(), position + direction.offset() + direction.offset()))
+            .filter(|&(over, to)| self.can_jump(position, over, to))
+            .collect()
+    }
+    /// Makes the jump from `position`, returning what to pass to `leave` to take it back.
+    fn enter(&mut self, position: Coordinate, over: Coordinate, to: Coordinate) -> (usize, usize) {
+        let entered = (self.removed.len(), self.captured.len());
+        if self.captures(over, CaptureRule::JumpOver) {
+            if self.definition.capture_timing_rule == CaptureTimingRule::AfterJump {
+                self.removed.push(over);
+            }
+            if !self.captured.contains(&over) {
+                self.captured.push(over);
+            }
+        }
+        if self.occupant(to).is_some() {
+            self.removed.push(to);
+            if !self.captured.contains(&to) {
+                self.captured.push(to);
+            }
+        }
+        self.visited.push(to);
+        self.jumps.push((position, to));
+        entered
+    }
+    fn leave(&mut self, (removed, captured): (usize, usize)) {
+        self.jumps.pop();
+        self.visited.pop();
+        self.removed.truncate(removed);
+        self.captured.truncate(captured);
+    }
+
+    /// The piece at `position` at this point of the sequence.
+    fn occupant(&self, position: Coordinate) -> Option<BoardPiece> {
+        if position == self.start || self.removed.contains(&position) {
+            None
+        } else {
+            self.board.get(position)
+        }
+    }
+    fn captures(&self, position: Coordinate, rule: CaptureRule) -> bool {
+        match self.occupant(position) {
+            None => false,
+            Some(target) => can_capture(self.ruleset, self.piece, target, rule),
+        }
+    }
+
+    fn can_jump(&self, from: Coordinate, over: Coordinate, to: Coordinate) -> bool {
+        if self.occupant(over).is_none() {
+            return false;
+        }
+        if !self.board.in_bounds(to) || self.ruleset.board_type.get_space(to) == Space::Invalid {
+            return false;
+        }
+        if self.occupant(to).is_some() && !self.captures(to, CaptureRule::JumpOn) {
+            return false;
+        }
+        if self.jumps.contains(&(from, to)) {
+            return false;
+        }
+        match self.definition.jump_rule {
+            JumpRule::NoBacktracking => !self.visited.contains(&to),
+            JumpRule::NoSameStart | JumpRule::Open => true,
+        }
+    }
+
+    fn can_end(&self, position: Coordinate) -> bool {
+        if self.definition.jump_rule == JumpRule::NoSameStart && position == self.start {
+            return false;
+        }
+        !self.only_to_goal || matches!(self.ruleset.board_type.get_space(position), Space::Goal(_))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::game::board::{Board, BoardPiece};
+    use crate::game::Coordinate;
+    use crate::game::direction::Directions;
+    use crate::game::jump::{jump_paths, jump_tree};
+    use crate::game::ruleset::piece_definition::{JumpLimit, JumpRule};
+    use crate::game::ruleset::standard::standard_rules;
+
+    /// A big piece at (5, 5) with pieces to jump at (4, 5), (2, 5), and (3, 6).
+    fn setup() -> Board {
+        let ruleset = standard_rules();
+        let mut board = Board::new(&ruleset);
+        board.place(&ruleset, Coordinate::new(5, 5), BoardPiece::new(0, 0)).unwrap();
+        board.place(&ruleset, Coordinate::new(4, 5), BoardPiece::new(1, 1)).unwrap();
+        board.place(&ruleset, Coordinate::new(2, 5), BoardPiece::new(1, 1)).unwrap();
+        board.place(&ruleset, Coordinate::new(3, 6), BoardPiece::new(1, 1)).unwrap();
+        board
+    }
+
+    #[test]
+    fn limited_jumps() {
+        let ruleset = standard_rules();
+        let mut board = setup();
+        let little = board.remove(Coordinate::new(5, 5)).unwrap();
+        board.place(&ruleset, Coordinate::new(5, 5), BoardPiece { piece: 1, ..little }).unwrap();
+        assert_eq!(jump_paths(&ruleset, &board, Coordinate::new(5, 5)), vec![vec![Coordinate::new(5, 5), Coordinate::new(3, 5)]]);
+    }
+
+    #[test]
+    fn unlimited_jumps() {
+        let mut ruleset = standard_rules();
+        let board = setup();
+        let start = Coordinate::new(5, 5);
+        let paths = jump_paths(&ruleset, &board, start);
+        assert!(paths.contains(&vec![start, Coordinate::new(3, 5)]));
+        assert!(paths.contains(&vec![start, Coordinate::new(3, 5), Coordinate::new(1, 5)]));
+        assert!(paths.contains(&vec![start, Coordinate::new(3, 5), Coordinate::new(3, 7)]));
+        // NoSameStart cannot end on the start but may pass it
+        assert!(!paths.contains(&vec![start, Coordinate::new(3, 5), start]));
+        let tree = jump_tree(&ruleset, &board, start);
+        assert!(tree.contains_path(&[start, Coordinate::new(3, 5), Coordinate::new(1, 5), Coordinate::new(3, 5), Coordinate::new(3, 7)]));
+        // Reuses the first jump
+        assert!(!tree.contains_path(&[start, Coordinate::new(3, 5), start, Coordinate::new(3, 5), Coordinate::new(1, 5)]));
+
+        ruleset.pieces[0].jump_rule = JumpRule::Open;
+        let paths = jump_paths(&ruleset, &board, start);
+        assert!(paths.contains(&vec![start, Coordinate::new(3, 5), start]));
+        assert!(paths.contains(&vec![start, Coordinate::new(3, 5), Coordinate::new(1, 5), Coordinate::new(3, 5), start]));
+        // Every jump between the three landing spots used once in each direction
+        assert_eq!(paths.iter().map(Vec::len).max(), Some(7));
+
+        ruleset.pieces[0].jump_rule = JumpRule::NoBacktracking;
+        let paths = jump_paths(&ruleset, &board, start);
+        assert_eq!(paths.len(), 3);
+
+        ruleset.pieces[0].jump_limit = JumpLimit::Unlimited { directions: Directions::CARDINAL };
+        let paths = jump_paths(&ruleset, &board, start);
+        assert_eq!(paths, vec![vec![start, Coordinate::new(3, 5)], vec![start, Coordinate::new(3, 5), Coordinate::new(1, 5)], vec![start, Coordinate::new(3, 5), Coordinate::new(3, 7)]]);
+    }
+}
@@ -5,6 +5,18 @@ pub use coordinate::rotate_coordinate;
 pub mod ruleset;
 
 mod coordinate;
+pub mod ai;
 pub mod board;
+pub mod capture;
 pub mod direction;
+pub mod hidden_placement;
+pub mod jump;
+pub mod movement;
+pub mod notation;
+pub mod perft;
+pub mod placement;
+pub mod position;
+pub mod state;
+pub mod victory;
+pub mod zobrist;
 
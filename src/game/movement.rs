@@ -0,0 +1,378 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::board::{Board, BoardPiece};
+use crate::game::capture::{can_capture, CapturedPiece, is_capture};
+use crate::game::Coordinate;
+use crate::game::direction::{Direction, Directions};
+use crate::game::jump::{jump_moves, jump_outcomes};
+use crate::game::ruleset::board_type::space::Space;
+use crate::game::ruleset::piece_definition::{CaptureRequirement, CaptureRule, GoalMovementRule, MoveRule};
+use crate::game::ruleset::Ruleset;
+
+/// A single piece's action for a turn.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Move {
+    /// Non-jumping move from one space to another.
+    Step {
+        from: Coordinate,
+        to: Coordinate,
+    },
+    /// A sequence of jumps, `path` holds the starting position followed by every landing position.
+    Jump {
+        path: Vec<Coordinate>,
+    },
+}
+impl Move {
+    /// Where the moving piece starts, `None` for an empty jump path.
+    pub fn from(&self) -> Option<Coordinate> {
+        match self {
+            Move::Step { from, .. } => Some(*from),
+            Move::Jump { path } => path.first().copied(),
+        }
+    }
+    /// Where the moving piece ends up, `None` for an empty jump path.
+    pub fn to(&self) -> Option<Coordinate> {
+        match self {
+            Move::Step { to, .. } => Some(*to),
+            Move::Jump { path } => path.last().copied(),
+        }
+    }
+}
+
+/// All legal step moves for the piece at `from`.
+///
+/// Moves stop at invalid and occupied spaces, but may end on a piece it can capture with `CaptureRule::Move`.
+/// A piece standing on a goal follows its `GoalMovementRule`, `OnlyToGoal` only restricts the destination.
+pub fn step_moves(ruleset: &Ruleset, board: &Board, from: Coordinate) -> Vec<Move> {
+    let piece = match board.get(from) {
+        None => return Vec::new(),
+        Some(piece) => piece,
+    };
+    let definition = &ruleset.pieces[piece.piece];
+    let goal_move_rule = match ruleset.board_type.get_space(from) {
+        Space::Goal(_) => definition.goal_move_rule,
+        Space::Normal | Space::Invalid => GoalMovementRule::Free,
+    };
+
+    let destinations = match (goal_move_rule, definition.move_rule) {
+        (GoalMovementRule::Locked, _) | (_, MoveRule::None) => return Vec::new(),
+        (_, MoveRule::SameDirection { limit, directions }) => same_direction_destinations(ruleset, board, piece, from, limit, directions),
+        (_, MoveRule::AnyDirection { limit, directions }) => any_direction_destinations(ruleset, board, piece, from, limit, directions),
+    };
+    destinations.into_iter()
+        .filter(|&to| goal_move_rule != GoalMovementRule::OnlyToGoal || matches!(ruleset.board_type.get_space(to), Space::Goal(_)))
+        .map(|to| Move::Step { from, to })
+        .collect()
+}
+
+/// All legal moves for the piece at `from`, ignoring forced captures.
+pub fn piece_moves(ruleset: &Ruleset, board: &Board, from: Coordinate) -> Vec<Move> {
+    let mut out = step_moves(ruleset, board, from);
+    out.append(&mut jump_moves(ruleset, board, from));
+    out
+}
+
+/// All legal moves for `seat`.
+///
+/// When any piece with `CaptureRequirement::Forced` can capture, only the capturing moves of the forced piece types
+/// with the highest priority are legal.
+/// Pieces with `CaptureRequirement::None` never force a capture.
+pub fn legal_moves(ruleset: &Ruleset, board: &Board, seat: u64) -> Vec<Move> {
+    let mut out = Vec::new();
+    let mut forced_priority = None;
+    let mut forced = Vec::new();
+    for (position, piece) in board.seat_pieces(seat) {
+        let moves = piece_moves(ruleset, board, position);
+        if let CaptureRequirement::Forced(priority) = ruleset.pieces[piece.piece].capture_requirement {
+            if forced_priority.unwrap_or(priority) <= priority {
+                let mut captures: Vec<_> = moves.iter()
+                    .filter(|&to_check| is_capture(ruleset, board, to_check))
+                    .cloned()
+                    .collect();
+                if !captures.is_empty() {
+                    if forced_priority != Some(priority) {
+                        forced.clear();
+                        forced_priority = Some(priority);
+                    }
+                    forced.append(&mut captures);
+                }
+            }
+        }
+        out.extend(moves);
+    }
+    if forced_priority.is_some() {
+        forced
+    } else {
+        out
+    }
+}
+
+/// Whether `seat` has any legal move, the same as `!legal_moves(..).is_empty()` without listing every jump path.
+pub fn has_legal_move(ruleset: &Ruleset, board: &Board, seat: u64) -> bool {
+    board.seat_pieces(seat).any(|(position, _)| {
+        !step_moves(ruleset, board, position).is_empty() || !jump_outcomes(ruleset, board, position).is_empty()
+    })
+}
+
+/// `legal_moves` with one jump path for each landing spot and set of captured pieces, each with the pieces it
+/// captures.
+///
+/// Jumps that give the same board are listed once, see `jump_outcomes`, so this stays fast for the AI when there are
+/// far too many jump paths to list. Follows the same forced capture rules as `legal_moves`.
+pub fn distinct_moves(ruleset: &Ruleset, board: &Board, seat: u64) -> Vec<(Move, Vec<CapturedPiece>)> {
+    let mut out = Vec::new();
+    let mut forced_priority = None;
+    let mut forced = Vec::new();
+    for (position, piece) in board.seat_pieces(seat) {
+        let mut moves: Vec<_> = step_moves(ruleset, board, position).into_iter()
+            .map(|step| {
+                let captured = step.to()
+                    .and_then(|to| Some(CapturedPiece { position: to, piece: board.get(to)? }))
+                    .into_iter()
+                    .collect();
+                (step, captured)
+            })
+            .collect();
+        moves.extend(jump_outcomes(ruleset, board, position).into_iter()
+            .map(|outcome| (Move::Jump { path: outcome.path }, outcome.captured)));
+
+        if let CaptureRequirement::Forced(priority) = ruleset.pieces[piece.piece].capture_requirement {
+            if forced_priority.unwrap_or(priority) <= priority {
+                let mut captures: Vec<_> = moves.iter()
+                    .filter(|(_, captured)| !captured.is_empty())
+                    .cloned()
+                    .collect();
+                if !captures.is_empty() {
+                    if forced_priority != Some(priority) {
+                        forced.clear();
+                        forced_priority = Some(priority);
+                    }
+                    forced.append(&mut captures);
+                }
+            }
+        }
+        out.extend(moves);
+    }
+    if forced_priority.is_some() {
+        forced
+    } else {
+        out
+    }
+}
+
+/// Whether a piece can pass through or stop on a space without capturing.
+pub(crate) fn is_open(ruleset: &Ruleset, board: &Board, coordinate: Coordinate) -> bool {
+    board.in_bounds(coordinate)
+        && ruleset.board_type.get_space(coordinate) != Space::Invalid
+        && board.get(coordinate).is_none()
+}
+
+/// Whether `piece` can end a step on a space by capturing what is there.
+fn can_move_capture(ruleset: &Ruleset, board: &Board, piece: BoardPiece, coordinate: Coordinate) -> bool {
+    match board.get(coordinate) {
+        None => false,
+        Some(target) => can_capture(ruleset, piece, target, CaptureRule::Move),
+    }
+}
+
+fn same_direction_destinations(ruleset: &Ruleset, board: &Board, piece: BoardPiece, from: Coordinate, limit: usize, directions: Directions) -> Vec<Coordinate> {
+    let mut out = Vec::new();
+    for direction in Vec::<Direction>::from(directions) {
+        let mut current = from;
+        for _ in 0..limit {
+            current += direction.offset();
+            if !is_open(ruleset, board, current) {
+                if can_move_capture(ruleset, board, piece, current) {
+                    out.push(current);
+                }
+                break;
+            }
+            out.push(current);
+        }
+    }
+    out
+}
+
+fn any_direction_destinations(ruleset: &Ruleset, board: &Board, piece: BoardPiece, from: Coordinate, limit: usize, directions: Directions) -> Vec<Coordinate> {
+    let directions = Vec::<Direction>::from(directions);
+    let mut visited: HashSet<_> = vec![from].into_iter().collect();
+    let mut out = Vec::new();
+    let mut frontier = vec![from];
+    for _ in 0..limit {
+        let mut next = Vec::new();
+        for &current in &frontier {
+            for direction in &directions {
+                let coordinate = current + direction.offset();
+                if visited.contains(&coordinate) {
+                    continue;
+                }
+                if is_open(ruleset, board, coordinate) {
+                    visited.insert(coordinate);
+                    next.push(coordinate);
+                } else if can_move_capture(ruleset, board, piece, coordinate) {
+                    // Captures end the move
+                    visited.insert(coordinate);
+                    out.push(coordinate);
+                }
+            }
+        }
+        if next.is_empty() {
+            break;
+        }
+        out.extend_from_slice(&next);
+        frontier = next;
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::{BTreeSet, HashSet};
+
+    use crate::game::board::{Board, BoardPiece};
+    use crate::game::capture::{apply_move, CapturedPiece};
+    use crate::game::Coordinate;
+    use crate::game::direction::Directions;
+    use crate::game::movement::{distinct_moves, has_legal_move, legal_moves, Move, step_moves};
+    use crate::game::ruleset::piece_definition::{CaptureRequirement, CaptureRule, CaptureTarget, CaptureTimingRule, GoalMovementRule, JumpRule, MoveRule};
+    use crate::game::ruleset::Ruleset;
+    use crate::game::ruleset::standard::standard_rules;
+
+    fn destinations(ruleset: &Ruleset, board: &Board, from: Coordinate) -> HashSet<Coordinate> {
+        step_moves(ruleset, board, from).into_iter().filter_map(|step| step.to()).collect()
+    }
+
+    #[test]
+    fn any_direction_blocked() {
+        let ruleset = standard_rules();
+        let mut board = Board::new(&ruleset);
+        board.place(&ruleset, Coordinate::new(5, 5), BoardPiece::new(0, 0)).unwrap();
+        assert_eq!(step_moves(&ruleset, &board, Coordinate::new(5, 5)).len(), 8);
+        board.place(&ruleset, Coordinate::new(4, 5), BoardPiece::new(1, 1)).unwrap();
+        assert!(!destinations(&ruleset, &board, Coordinate::new(5, 5)).contains(&Coordinate::new(4, 5)));
+        assert_eq!(step_moves(&ruleset, &board, Coordinate::new(5, 5)).len(), 7);
+
+        // Corner next to invalid goal row spaces
+        board.place(&ruleset, Coordinate::new(1, 0), BoardPiece::new(0, 1)).unwrap();
+        assert_eq!(step_moves(&ruleset, &board, Coordinate::new(1, 0)).len(), 3);
+    }
+
+    #[test]
+    fn same_direction_limit() {
+        let mut ruleset = standard_rules();
+        ruleset.pieces[0].move_rule = MoveRule::SameDirection { limit: 3, directions: Directions::NORTH | Directions::EAST };
+        let mut board = Board::new(&ruleset);
+        board.place(&ruleset, Coordinate::new(5, 5), BoardPiece::new(0, 0)).unwrap();
+        board.place(&ruleset, Coordinate::new(5, 7), BoardPiece::new(1, 1)).unwrap();
+        let expected: HashSet<_> = vec![Coordinate::new(4, 5), Coordinate::new(3, 5), Coordinate::new(2, 5), Coordinate::new(5, 6)].into_iter().collect();
+        assert_eq!(destinations(&ruleset, &board, Coordinate::new(5, 5)), expected);
+    }
+
+    #[test]
+    fn any_direction_limit() {
+        let mut ruleset = standard_rules();
+        ruleset.pieces[0].move_rule = MoveRule::AnyDirection { limit: 2, directions: Directions::CARDINAL };
+        let mut board = Board::new(&ruleset);
+        board.place(&ruleset, Coordinate::new(5, 5), BoardPiece::new(0, 0)).unwrap();
+        // Diamond of radius 2 without the start
+        assert_eq!(step_moves(&ruleset, &board, Coordinate::new(5, 5)).len(), 12);
+    }
+
+    #[test]
+    fn goal_movement_rules() {
+        let mut ruleset = standard_rules();
+        let mut board = Board::new(&ruleset);
+        let goal = Coordinate::new(11, 4);
+        board.place(&ruleset, goal, BoardPiece::new(0, 0)).unwrap();
+        assert_eq!(destinations(&ruleset, &board, goal).len(), 4);
+
+        ruleset.pieces[0].goal_move_rule = GoalMovementRule::OnlyToGoal;
+        assert_eq!(step_moves(&ruleset, &board, goal), vec![Move::Step { from: goal, to: Coordinate::new(11, 5) }]);
+
+        ruleset.pieces[0].goal_move_rule = GoalMovementRule::Locked;
+        assert!(step_moves(&ruleset, &board, goal).is_empty());
+    }
+
+    #[test]
+    fn forced_captures() {
+        let mut ruleset = standard_rules();
+        let mut board = Board::new(&ruleset);
+        // Big at (5, 5) can capture (4, 5), little at (8, 5) can capture (7, 5)
+        board.place(&ruleset, Coordinate::new(5, 5), BoardPiece::new(0, 0)).unwrap();
+        board.place(&ruleset, Coordinate::new(4, 5), BoardPiece::new(1, 1)).unwrap();
+        board.place(&ruleset, Coordinate::new(8, 5), BoardPiece::new(0, 1)).unwrap();
+        board.place(&ruleset, Coordinate::new(7, 5), BoardPiece::new(1, 1)).unwrap();
+        let moves = legal_moves(&ruleset, &board, 0);
+        assert_eq!(moves, vec![Move::Jump { path: vec![Coordinate::new(5, 5), Coordinate::new(3, 5)] }]);
+
+        // Higher priority wins
+        ruleset.pieces[1].capture_requirement = CaptureRequirement::Forced(20);
+        let moves = legal_moves(&ruleset, &board, 0);
+        assert_eq!(moves, vec![Move::Jump { path: vec![Coordinate::new(8, 5), Coordinate::new(6, 5)] }]);
+
+        // Equal priority allows either
+        ruleset.pieces[1].capture_requirement = CaptureRequirement::Forced(10);
+        assert_eq!(legal_moves(&ruleset, &board, 0).len(), 2);
+
+        // Nothing forced
+        ruleset.pieces[0].capture_requirement = CaptureRequirement::None;
+        ruleset.pieces[1].capture_requirement = CaptureRequirement::None;
+        assert_eq!(legal_moves(&ruleset, &board, 0).len(), 7 + 1 + 7 + 1);
+    }
+
+    /// Checks `distinct_moves` and `has_legal_move` against playing out every legal move for each seat.
+    fn check_distinct(ruleset: &Ruleset, board: &Board) {
+        let mut board = board.clone();
+        for _ in 0..board.seats() {
+            let seat = board.current_seat();
+            // Where the move starts and ends and what it captures, which decide the board after it
+            let result = |action: &Move, captured: &[CapturedPiece]| {
+                let captured: BTreeSet<_> = captured.iter().map(|capture| (capture.position.row, capture.position.column)).collect();
+                (matches!(action, Move::Jump { .. }), action.from(), action.to(), captured)
+            };
+            let moves = legal_moves(ruleset, &board, seat);
+            let distinct = distinct_moves(ruleset, &board, seat);
+            let mut results = HashSet::new();
+            for (action, captured) in &distinct {
+                assert!(moves.contains(action));
+                let applied = apply_move(ruleset, &mut board.clone(), action).unwrap();
+                assert_eq!(result(action, captured), result(action, &applied));
+                assert!(results.insert(result(action, captured)));
+            }
+            for action in &moves {
+                let applied = apply_move(ruleset, &mut board.clone(), action).unwrap();
+                assert!(results.contains(&result(action, &applied)));
+            }
+            assert_eq!(has_legal_move(ruleset, &board, seat), !moves.is_empty());
+            board.end_turn();
+        }
+    }
+
+    #[test]
+    fn distinct_moves_cover_every_result() {
+        let mut ruleset = standard_rules();
+        let mut board = Board::initial(&ruleset).unwrap();
+        for _ in 0..6 {
+            check_distinct(&ruleset, &board);
+            let action = legal_moves(&ruleset, &board, board.current_seat()).remove(0);
+            apply_move(&ruleset, &mut board, &action).unwrap();
+        }
+
+        let mut board = Board::new(&ruleset);
+        for &(row, column, seat, piece) in &[(5, 5, 0, 0), (4, 5, 1, 1), (3, 6, 0, 1), (2, 5, 1, 0), (8, 5, 0, 1), (7, 5, 1, 1)] {
+            board.place(&ruleset, Coordinate::new(row, column), BoardPiece::new(seat, piece)).unwrap();
+        }
+        check_distinct(&ruleset, &board);
+        ruleset.pieces[0].capture_requirement = CaptureRequirement::None;
+        check_distinct(&ruleset, &board);
+        ruleset.pieces[0].jump_rule = JumpRule::Open;
+        ruleset.pieces[0].capture_timing_rule = CaptureTimingRule::AfterJump;
+        ruleset.pieces[0].capture_rules.insert(CaptureRule::JumpOver, CaptureTarget::All);
+        check_distinct(&ruleset, &board);
+        ruleset.pieces[1].capture_rules.insert(CaptureRule::JumpOn, CaptureTarget::EnemyOnly);
+        ruleset.pieces[1].capture_rules.insert(CaptureRule::Move, CaptureTarget::EnemyOnly);
+        check_distinct(&ruleset, &board);
+    }
+}
@@ -0,0 +1,451 @@
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Debug, Display, Formatter};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::board::{Board, BoardError};
+use crate::game::capture::apply_move;
+use crate::game::Coordinate;
+use crate::game::hidden_placement::{HiddenPiece, lock_hidden_placement};
+use crate::game::movement::{legal_moves, Move};
+use crate::game::placement::{finish_placement, place_piece, PlacementError};
+use crate::game::ruleset::board_type::space::Space;
+use crate::game::ruleset::Ruleset;
+
+/// Writes a coordinate as column letters followed by the row, `Coordinate::new(3, 27)` is `ab3`.
+///
+/// Rows are not shifted so row 0 is the top goal row on rectangular boards.
+pub fn format_coordinate(coordinate: Coordinate) -> String {
+    format!("{}{}", format_letters(coordinate.column as usize, b'a'), coordinate.row)
+}
+/// Reads a coordinate written by `format_coordinate`.
+pub fn parse_coordinate(text: &str) -> NotationResult<Coordinate> {
+    let invalid = || NotationError::InvalidCoordinate(text.to_string());
+    let split = text.find(|c: char| !c.is_ascii_lowercase()).ok_or_else(invalid)?;
+    let (letters, row) = text.split_at(split);
+    let column = parse_letters(letters, b'a').filter(|&column| column <= i16::MAX as usize).ok_or_else(invalid)?;
+    let row = row.parse::<u16>().ok().filter(|&row| row <= i16::MAX as u16).ok_or_else(invalid)?;
+    Ok(Coordinate::new(row as i16, column as i16))
+}
+
+/// Writes an index as letters starting at `first`, `a` to `z` then `aa` and on.
+pub(crate) fn format_letters(index: usize, first: u8) -> String {
+    let mut letters = Vec::new();
+    let mut rest = index + 1;
+    while rest > 0 {
+        rest -= 1;
+        letters.push(first + (rest % 26) as u8);
+        rest /= 26;
+    }
+    letters.reverse();
+    String::from_utf8(letters).expect("Letters are ascii")
+}
+/// Reads an index written by `format_letters`, `None` if empty, not all letters, or too large.
+pub(crate) fn parse_letters(text: &str, first: u8) -> Option<usize> {
+    if text.is_empty() {
+        return None;
+    }
+    let mut index: usize = 0;
+    for letter in text.bytes() {
+        if letter < first || letter >= first + 26 {
+            return None;
+        }
+        index = index.checked_mul(26)?.checked_add((letter - first) as usize + 1)?;
+    }
+    Some(index - 1)
+}
+
+/// A move along with the details needed to read it without the board.
+///
+/// Written as `piece:from-to` for steps and `piece:from>landing>...` for jumps, followed by `*` and the captured
+/// spaces if anything was captured and `+` if the move ends on a goal, for example `1:c3>e5>g3*d4,f4+`.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct NotatedMove {
+    /// Index into `Ruleset::pieces` of the moving piece
+    pub piece: usize,
+    pub action: Move,
+    /// Where the captured pieces were, in capture order
+    pub captures: Vec<Coordinate>,
+    /// Whether the move ends on a goal
+    pub goal: bool,
+}
+impl NotatedMove {
+    /// Notates `action` played by the current seat on `board`.
+    pub fn new(ruleset: &Ruleset, board: &Board, action: Move) -> NotationResult<Self> {
+        let from = action.from().ok_or(BoardError::JumpPathTooShort(0))?;
+        let piece = board.get(from).ok_or(BoardError::NoPieceAt(from))?.piece;
+        let to = action.to().ok_or(BoardError::JumpPathTooShort(0))?;
+        let mut after = board.clone();
+        let captures = apply_move(ruleset, &mut after, &action)?.into_iter()
+            .map(|captured| captured.position)
+            .collect();
+        let goal = matches!(ruleset.board_type.get_space(to), Space::Goal(_));
+        Ok(Self { piece, action, captures, goal })
+    }
+}
+impl Display for NotatedMove {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let path = match &self.action {
+            Move::Step { from, to } => format!("{}-{}", format_coordinate(*from), format_coordinate(*to)),
+            Move::Jump { path } => path.iter().map(|&position| format_coordinate(position)).collect::<Vec<_>>().join(">"),
+        };
+        write!(f, "{}:{}", self.piece, path)?;
+        if !self.captures.is_empty() {
+            let captures: Vec<_> = self.captures.iter().map(|&position| format_coordinate(position)).collect();
+            write!(f, "*{}", captures.join(","))?;
+        }
+        if self.goal {
+            write!(f, "+")?;
+        }
+        Ok(())
+    }
+}
+impl FromStr for NotatedMove {
+    type Err = NotationError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let invalid = || NotationError::InvalidMove(text.to_string());
+        let (piece, rest) = split_once(text, ':').ok_or_else(invalid)?;
+        let piece = piece.parse().map_err(|_| invalid())?;
+        let (rest, goal) = match rest.strip_suffix('+') {
+            None => (rest, false),
+            Some(rest) => (rest, true),
+        };
+        let (path, captures) = match split_once(rest, '*') {
+            None => (rest, Vec::new()),
+            Some((path, captures)) => (path, captures.split(',').map(parse_coordinate).collect::<NotationResult<_>>()?),
+        };
+        let action = if let Some((from, to)) = split_once(path, '-') {
+            Move::Step { from: parse_coordinate(from)?, to: parse_coordinate(to)? }
+        } else {
+            let path: Vec<_> = path.split('>').map(parse_coordinate).collect::<NotationResult<_>>()?;
+            if path.len() < 2 {
+                return Err(invalid());
+            }
+            Move::Jump { path }
+        };
+        Ok(Self { piece, action, captures, goal })
+    }
+}
+
+/// A single entry in a game record.
+///
+/// Placements are written `piece@space`, finishing placement `--`, and hidden placements as the seat followed by
+/// its placements, for example `1{0@a1,1@b1}`.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum RecordEntry {
+    Move(NotatedMove),
+    Place {
+        coordinate: Coordinate,
+        piece: usize,
+    },
+    FinishPlacement,
+    HiddenPlacement {
+        seat: u64,
+        pieces: Vec<HiddenPiece>,
+    },
+}
+impl Display for RecordEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordEntry::Move(notated) => write!(f, "{}", notated),
+            RecordEntry::Place { coordinate, piece } => write!(f, "{}@{}", piece, format_coordinate(*coordinate)),
+            RecordEntry::FinishPlacement => write!(f, "--"),
+            RecordEntry::HiddenPlacement { seat, pieces } => {
+                let pieces: Vec<_> = pieces.iter().map(|piece| format!("{}@{}", piece.piece, format_coordinate(piece.coordinate))).collect();
+                write!(f, "{}{{{}}}", seat, pieces.join(","))
+            }
+        }
+    }
+}
+impl FromStr for RecordEntry {
+    type Err = NotationError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let invalid = || NotationError::InvalidMove(text.to_string());
+        let parse_place = |place: &str| -> NotationResult<HiddenPiece> {
+            let (piece, coordinate) = split_once(place, '@').ok_or_else(invalid)?;
+            Ok(HiddenPiece { coordinate: parse_coordinate(coordinate)?, piece: piece.parse().map_err(|_| invalid())? })
+        };
+        if text == "--" {
+            Ok(RecordEntry::FinishPlacement)
+        } else if let Some((seat, pieces)) = split_once(text, '{') {
+            let pieces = pieces.strip_suffix('}').ok_or_else(invalid)?;
+            let pieces = if pieces.is_empty() {
+                Vec::new()
+            } else {
+                pieces.split(',').map(parse_place).collect::<NotationResult<_>>()?
+            };
+            Ok(RecordEntry::HiddenPlacement { seat: seat.parse().map_err(|_| invalid())?, pieces })
+        } else if text.contains('@') {
+            let HiddenPiece { coordinate, piece } = parse_place(text)?;
+            Ok(RecordEntry::Place { coordinate, piece })
+        } else {
+            Ok(RecordEntry::Move(text.parse()?))
+        }
+    }
+}
+
+/// The ruleset a game record was played with.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum RecordRuleset {
+    /// The full ruleset, written as json on a single line.
+    Embedded(Box<Ruleset>),
+    /// A `DatabaseRuleset` by id and version.
+    Reference {
+        id: u64,
+        version: u32,
+    },
+}
+
+/// A full game that can be shared and replayed.
+///
+/// Written as header lines, an empty line, then one entry per line numbered by ply:
+/// ```text
+/// ruleset-ref 12 3
+/// seats 2
+/// player 0 alice
+/// player 1 bob
+///
+/// 1. 0:b1-b2
+/// 2. 1:c8>c6*c7
+/// ```
+/// An embedded ruleset uses `ruleset <json>` instead of `ruleset-ref <id> <version>`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GameRecord {
+    pub ruleset: RecordRuleset,
+    /// Player names indexed by seat, empty if unknown
+    pub players: Vec<String>,
+    pub entries: Vec<RecordEntry>,
+}
+impl GameRecord {
+    pub fn new(ruleset: RecordRuleset, seats: u64) -> Self {
+        Self {
+            ruleset,
+            players: vec![String::new(); seats as usize],
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn seats(&self) -> u64 {
+        self.players.len() as u64
+    }
+
+    /// Plays every entry from the initial board, checking moves are legal and match their notation.
+    ///
+    /// `ruleset` must be the embedded ruleset or the referenced one loaded from the database.
+    pub fn replay(&self, ruleset: &Ruleset) -> NotationResult<Board> {
+        let mut board = Board::initial(ruleset)?;
+        for (ply, entry) in self.entries.iter().enumerate() {
+            match entry {
+                RecordEntry::Move(notated) => {
+                    if !legal_moves(ruleset, &board, board.current_seat()).contains(&notated.action) {
+                        return Err(NotationError::IllegalMove(ply));
+                    }
+                    if NotatedMove::new(ruleset, &board, notated.action.clone())? != *notated {
+                        return Err(NotationError::NotationMismatch(ply));
+                    }
+                    apply_move(ruleset, &mut board, &notated.action)?;
+                }
+                RecordEntry::Place { coordinate, piece } => place_piece(ruleset, &mut board, *coordinate, *piece)?,
+                RecordEntry::FinishPlacement => finish_placement(ruleset, &mut board)?,
+                RecordEntry::HiddenPlacement { seat, pieces } => {
+                    lock_hidden_placement(ruleset, &mut board, *seat, pieces.clone(), None)?;
+                }
+            }
+        }
+        Ok(board)
+    }
+}
+impl Display for GameRecord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.ruleset {
+            RecordRuleset::Embedded(ruleset) => writeln!(f, "ruleset {}", serde_json::to_string(ruleset).map_err(|_| fmt::Error)?)?,
+            RecordRuleset::Reference { id, version } => writeln!(f, "ruleset-ref {} {}", id, version)?,
+        }
+        writeln!(f, "seats {}", self.seats())?;
+        for (seat, player) in self.players.iter().enumerate() {
+            if !player.is_empty() {
+                writeln!(f, "player {} {}", seat, player)?;
+            }
+        }
+        writeln!(f)?;
+        for (ply, entry) in self.entries.iter().enumerate() {
+            writeln!(f, "{}. {}", ply + 1, entry)?;
+        }
+        Ok(())
+    }
+}
+impl FromStr for GameRecord {
+    type Err = NotationError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut lines = text.lines();
+        let mut ruleset = None;
+        let mut players = Vec::new();
+        for line in &mut lines {
+            let line = line.trim();
+            if line.is_empty() {
+                break;
+            }
+            let invalid = || NotationError::InvalidHeader(line.to_string());
+            let (key, value) = split_once(line, ' ').ok_or_else(invalid)?;
+            match key {
+                "ruleset" => ruleset = Some(RecordRuleset::Embedded(serde_json::from_str(value).map_err(|error| NotationError::InvalidRuleset(error.to_string()))?)),
+                "ruleset-ref" => {
+                    let (id, version) = split_once(value, ' ').ok_or_else(invalid)?;
+                    ruleset = Some(RecordRuleset::Reference {
+                        id: id.parse().map_err(|_| invalid())?,
+                        version: version.parse().map_err(|_| invalid())?,
+                    });
+                }
+                // Records are untrusted, seats are capped at the largest board dimension
+                "seats" => players = vec![String::new(); value.parse::<u8>().map_err(|_| invalid())? as usize],
+                "player" => {
+                    let (seat, name) = split_once(value, ' ').ok_or_else(invalid)?;
+                    let seat: usize = seat.parse().map_err(|_| invalid())?;
+                    *players.get_mut(seat).ok_or_else(invalid)? = name.to_string();
+                }
+                _ => return Err(invalid()),
+            }
+        }
+        let entries = lines
+            .flat_map(str::split_whitespace)
+            .filter(|token| !token.ends_with('.'))
+            .map(str::parse)
+            .collect::<NotationResult<_>>()?;
+        Ok(Self {
+            ruleset: ruleset.ok_or(NotationError::MissingRuleset)?,
+            players,
+            entries,
+        })
+    }
+}
+
+fn split_once(text: &str, separator: char) -> Option<(&str, &str)> {
+    text.find(separator).map(|index| (&text[..index], &text[index + separator.len_utf8()..]))
+}
+
+pub type NotationResult<T> = Result<T, NotationError>;
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum NotationError {
+    InvalidCoordinate(String),
+    InvalidMove(String),
+    InvalidHeader(String),
+    InvalidPosition(String),
+    InvalidRuleset(String),
+    MissingRuleset,
+    /// The move at the ply is not legal
+    IllegalMove(usize),
+    /// The move at the ply does not have the recorded piece, captures, or goal
+    NotationMismatch(usize),
+    BoardError(BoardError),
+    PlacementError(PlacementError),
+}
+impl Display for NotationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        <Self as Debug>::fmt(self, f)
+    }
+}
+impl Error for NotationError {
+    fn cause(&self) -> Option<&dyn Error> {
+        match self {
+            Self::BoardError(error) => Some(error),
+            Self::PlacementError(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+impl From<BoardError> for NotationError {
+    fn from(from: BoardError) -> Self {
+        Self::BoardError(from)
+    }
+}
+impl From<PlacementError> for NotationError {
+    fn from(from: PlacementError) -> Self {
+        Self::PlacementError(from)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::game::board::Board;
+    use crate::game::Coordinate;
+    use crate::game::movement::{legal_moves, Move};
+    use crate::game::notation::{format_coordinate, GameRecord, NotatedMove, NotationError, parse_coordinate, RecordEntry, RecordRuleset};
+    use crate::game::ruleset::standard::standard_rules;
+
+    #[test]
+    fn coordinates() {
+        for &(row, column, text) in &[(0, 0, "a0"), (11, 25, "z11"), (3, 26, "aa3"), (7, 701, "zz7"), (1, 702, "aaa1")] {
+            assert_eq!(format_coordinate(Coordinate::new(row, column)), text);
+            assert_eq!(parse_coordinate(text), Ok(Coordinate::new(row, column)));
+        }
+        for &text in &["", "a", "4", "A4", "a-1", "a4b"] {
+            assert_eq!(parse_coordinate(text), Err(NotationError::InvalidCoordinate(text.to_string())));
+        }
+    }
+
+    #[test]
+    fn moves() {
+        let jump = NotatedMove {
+            piece: 1,
+            action: Move::Jump { path: vec![Coordinate::new(3, 2), Coordinate::new(5, 4), Coordinate::new(3, 6)] },
+            captures: vec![Coordinate::new(4, 3), Coordinate::new(4, 5)],
+            goal: true,
+        };
+        assert_eq!(jump.to_string(), "1:c3>e5>g3*d4,f4+");
+        assert_eq!("1:c3>e5>g3*d4,f4+".parse(), Ok(jump));
+        let step = NotatedMove {
+            piece: 0,
+            action: Move::Step { from: Coordinate::new(1, 1), to: Coordinate::new(2, 1) },
+            captures: vec![],
+            goal: false,
+        };
+        assert_eq!("0:b1-b2".parse(), Ok(step));
+        assert!("0:b1".parse::<NotatedMove>().is_err());
+        assert_eq!("1{0@a1,1@b1}".parse::<RecordEntry>().unwrap().to_string(), "1{0@a1,1@b1}");
+        assert_eq!("--".parse(), Ok(RecordEntry::FinishPlacement));
+    }
+
+    #[test]
+    fn record_round_trip() {
+        let ruleset = standard_rules();
+        let mut board = Board::initial(&ruleset).unwrap();
+        let mut record = GameRecord::new(RecordRuleset::Reference { id: 12, version: 3 }, 2);
+        record.players[1] = "some player".to_string();
+        for _ in 0..6 {
+            let action = legal_moves(&ruleset, &board, board.current_seat()).remove(0);
+            let notated = NotatedMove::new(&ruleset, &board, action.clone()).unwrap();
+            crate::game::capture::apply_move(&ruleset, &mut board, &action).unwrap();
+            record.entries.push(RecordEntry::Move(notated));
+        }
+        assert_eq!(record.replay(&ruleset), Ok(board));
+
+        let text = record.to_string();
+        let parsed: GameRecord = text.parse().unwrap();
+        assert_eq!(parsed.to_string(), text);
+        assert_eq!(parsed.players, vec![String::new(), "some player".to_string()]);
+
+        record.ruleset = RecordRuleset::Embedded(Box::new(ruleset.clone()));
+        let parsed: GameRecord = record.to_string().parse().unwrap();
+        assert!(matches!(parsed.ruleset, RecordRuleset::Embedded(_)));
+
+        if let RecordEntry::Move(notated) = &mut record.entries[2] {
+            notated.piece = 5;
+        }
+        assert_eq!(record.replay(&ruleset), Err(NotationError::NotationMismatch(2)));
+    }
+
+    #[test]
+    fn record_seat_limit() {
+        let text = "ruleset-ref 12 3\nseats {}\n\n";
+        assert_eq!(text.replace("{}", "255").parse::<GameRecord>().unwrap().players.len(), 255);
+        for seats in &["256", "18446744073709551615"] {
+            let header = format!("seats {}", seats);
+            assert_eq!(text.replace("{}", seats).parse::<GameRecord>().err(), Some(NotationError::InvalidHeader(header)));
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use crate::game::ruleset::Ruleset;
+use crate::game::state::GameState;
+use crate::game::victory::evaluate_victory;
+
+/// Counts the action sequences of length `depth` from the current position.
+///
+/// Sequences stop early once any seat has won or lost, those are not counted.
+/// The state is left as it was.
+pub fn perft(ruleset: &Ruleset, state: &mut GameState, depth: usize) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    let mut count = 0;
+    for action in state.legal_actions(ruleset) {
+        state.apply(ruleset, action).expect("Legal action could not be applied");
+        if evaluate_victory(ruleset, state.board()).iter().all(|outcome| outcome.is_playing()) {
+            count += perft(ruleset, state, depth - 1);
+        }
+        state.undo();
+    }
+    count
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::File;
+
+    use crate::game::perft::perft;
+    use crate::game::ruleset::Ruleset;
+    use crate::game::ruleset::standard::standard_rules;
+    use crate::game::state::GameState;
+    use crate::game::victory::{evaluate_victory, SeatOutcome};
+
+    fn load(path: &str) -> Ruleset {
+        serde_json::from_reader(File::open(path).expect("Could not open ruleset")).expect("Could not read ruleset")
+    }
+    /// Checks the counts for depth 1 and on, and that the state is unchanged afterwards.
+    fn check_counts(ruleset: &Ruleset, expected: &[u64]) {
+        ruleset.verify().expect("Invalid ruleset");
+        let mut state = GameState::new(ruleset).unwrap();
+        let start = state.board().clone();
+        let counts: Vec<_> = (1..=expected.len()).map(|depth| perft(ruleset, &mut state, depth)).collect();
+        assert_eq!(counts, expected);
+        assert_eq!(state.board(), &start);
+    }
+
+    #[test]
+    fn standard_rules_counts() {
+        // Unlimited jump sequences make depth 3 too slow for a debug test
+        check_counts(&standard_rules(), &[39, 1820]);
+    }
+
+    #[test]
+    fn example_standard_counts() {
+        check_counts(&load("example/standard_rules.json"), &[34, 14446]);
+    }
+
+    #[test]
+    fn example_test_rules_counts() {
+        // Both seats are allied, with no other team left `AllCaptured` is met before the first move
+        let ruleset = load("example/test_rules.json");
+        let state = GameState::new(&ruleset).unwrap();
+        assert!(evaluate_victory(&ruleset, state.board()).iter().all(|outcome| matches!(outcome, SeatOutcome::Won(_))));
+        assert_eq!(state.legal_actions(&ruleset).len(), 17);
+        check_counts(&ruleset, &[0, 0, 0]);
+    }
+}
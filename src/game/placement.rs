@@ -0,0 +1,372 @@
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Debug, Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::board::{Board, BoardError, BoardPiece};
+use crate::game::Coordinate;
+use crate::game::hidden_placement::HiddenSubmission;
+use crate::game::ruleset::board_type::space::Space;
+use crate::game::ruleset::Ruleset;
+use crate::game::ruleset::starting_positions::alteration_type::AlternationType;
+use crate::game::ruleset::starting_positions::piece_limit::PieceLimit;
+use crate::game::ruleset::starting_positions::placement_area::PlacementArea;
+use crate::game::ruleset::starting_positions::StartingPositions;
+
+/// Progress of the placement phase for `StartingPositions::Placement`.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct PlacementState {
+    /// Pieces placed, indexed by seat then piece index
+    placed: Vec<Vec<usize>>,
+    /// Pieces placed for `TurnsCount` or points placed for `TurnsPoints` by the current seat this turn
+    placed_this_turn: usize,
+    /// Seats that will not place any more pieces
+    finished: Vec<bool>,
+    /// Submissions for `AlternationType::Hidden`, indexed by seat
+    hidden: Vec<HiddenSubmission>,
+}
+impl PlacementState {
+    pub fn new(ruleset: &Ruleset) -> Self {
+        Self {
+            placed: vec![vec![0; ruleset.pieces.len()]; ruleset.seats as usize],
+            placed_this_turn: 0,
+            finished: vec![false; ruleset.seats as usize],
+            hidden: vec![HiddenSubmission::Waiting; ruleset.seats as usize],
+        }
+    }
+    pub(crate) fn from_parts(placed: Vec<Vec<usize>>, placed_this_turn: usize, finished: Vec<bool>, hidden: Vec<HiddenSubmission>) -> Self {
+        Self { placed, placed_this_turn, finished, hidden }
+    }
+
+    /// Number of each piece index `seat` has placed.
+    pub fn placed(&self, seat: u64) -> &[usize] {
+        &self.placed[seat as usize]
+    }
+    /// Total point value of the pieces `seat` has placed.
+    pub fn placed_points(&self, ruleset: &Ruleset, seat: u64) -> usize {
+        self.placed(seat).iter().enumerate()
+            .map(|(piece, count)| count * ruleset.piece_value(piece))
+            .sum()
+    }
+    pub fn placed_this_turn(&self) -> usize {
+        self.placed_this_turn
+    }
+    pub fn is_finished(&self, seat: u64) -> bool {
+        self.finished[seat as usize]
+    }
+    /// Submissions for `AlternationType::Hidden`, indexed by seat.
+    pub fn hidden(&self) -> &[HiddenSubmission] {
+        &self.hidden
+    }
+    pub(crate) fn hidden_mut(&mut self) -> &mut [HiddenSubmission] {
+        &mut self.hidden
+    }
+    pub(crate) fn add_placed(&mut self, seat: u64, piece: usize) {
+        self.placed[seat as usize][piece] += 1;
+    }
+}
+
+/// The placement rules of a ruleset, `None` if it does not use placement.
+fn placement_rules(ruleset: &Ruleset) -> Option<(u64, AlternationType, &PlacementArea, &HashSet<PieceLimit>)> {
+    match &ruleset.starting_positions {
+        StartingPositions::Placement { first_seat, alternation_type, placement_area, piece_limits } => Some((*first_seat, *alternation_type, placement_area, piece_limits)),
+        _ => None,
+    }
+}
+
+/// Checks that `seat` could place `piece` on `coordinate` ignoring turn order.
+pub fn check_placement(ruleset: &Ruleset, board: &Board, seat: u64, coordinate: Coordinate, piece: usize) -> PlacementResult<()> {
+    let (_, _, placement_area, _) = placement_rules(ruleset).ok_or(PlacementError::NotPlacementPhase)?;
+    let state = board.placement().ok_or(PlacementError::NotPlacementPhase)?;
+    if ruleset.get_piece(piece).is_none() {
+        return Err(BoardError::PieceIndexNotFound(piece).into());
+    }
+    check_piece_limits(ruleset, state, seat, piece)?;
+    if !placement_area.seat_area(ruleset, seat).contains(&coordinate) {
+        return Err(PlacementError::OutsideArea(coordinate));
+    }
+    if ruleset.board_type.get_space(coordinate) == Space::Invalid {
+        return Err(BoardError::InvalidSpace(coordinate).into());
+    }
+    if board.get(coordinate).is_some() {
+        return Err(BoardError::Occupied(coordinate).into());
+    }
+    Ok(())
+}
+
+/// Places `piece` for the current seat and passes the turn as the `AlternationType` requires.
+///
+/// The board moves on to the movement phase once every seat has finished placing.
+pub fn place_piece(ruleset: &Ruleset, board: &mut Board, coordinate: Coordinate, piece: usize) -> PlacementResult<()> {
+    let (_, alternation_type, _, _) = placement_rules(ruleset).ok_or(PlacementError::NotPlacementPhase)?;
+    let seat = board.current_seat();
+    check_placement(ruleset, board, seat, coordinate, piece)?;
+    let state = board.placement().ok_or(PlacementError::NotPlacementPhase)?;
+    if let AlternationType::Hidden = alternation_type {
+        return Err(PlacementError::HiddenPlacement);
+    }
+    if !fits_turn(ruleset, alternation_type, state, piece) {
+        return Err(PlacementError::TurnPointsExceeded);
+    }
+
+    board.place(ruleset, coordinate, BoardPiece::new(seat, piece))?;
+    let state = board.placement_mut().ok_or(PlacementError::NotPlacementPhase)?;
+    state.add_placed(seat, piece);
+    state.placed_this_turn += match alternation_type {
+        AlternationType::TurnsPoints { .. } => ruleset.piece_value(piece),
+        _ => 1,
+    };
+    advance(ruleset, board);
+    Ok(())
+}
+
+/// The current seat stops placing pieces for the rest of the placement phase.
+pub fn finish_placement(ruleset: &Ruleset, board: &mut Board) -> PlacementResult<()> {
+    let seat = board.current_seat();
+    let state = board.placement_mut().ok_or(PlacementError::NotPlacementPhase)?;
+    state.finished[seat as usize] = true;
+    advance(ruleset, board);
+    Ok(())
+}
+
+/// Every placement the current seat can make as (coordinate, piece index).
+pub fn placement_moves(ruleset: &Ruleset, board: &Board) -> Vec<(Coordinate, usize)> {
+    let (_, alternation_type, placement_area, _) = match placement_rules(ruleset) {
+        None => return Vec::new(),
+        Some(rules) => rules,
+    };
+    let state = match board.placement() {
+        None => return Vec::new(),
+        Some(state) => state,
+    };
+    let seat = board.current_seat();
+    let pieces: Vec<_> = (0..ruleset.pieces.len())
+        .filter(|&piece| check_piece_limits(ruleset, state, seat, piece).is_ok() && fits_turn(ruleset, alternation_type, state, piece))
+        .collect();
+    let mut area: Vec<_> = placement_area.seat_area(ruleset, seat).into_iter()
+        .filter(|&coordinate| ruleset.board_type.get_space(coordinate) != Space::Invalid && board.in_bounds(coordinate) && board.get(coordinate).is_none())
+        .collect();
+    area.sort_by_key(|coordinate| (coordinate.row, coordinate.column));
+    area.into_iter()
+        .flat_map(|coordinate| pieces.iter().map(move |&piece| (coordinate, piece)))
+        .collect()
+}
+
+pub(crate) fn check_piece_limits(ruleset: &Ruleset, state: &PlacementState, seat: u64, piece: usize) -> PlacementResult<()> {
+    let (_, _, _, piece_limits) = placement_rules(ruleset).ok_or(PlacementError::NotPlacementPhase)?;
+    let placed = state.placed(seat);
+    for piece_limit in piece_limits {
+        match piece_limit {
+            PieceLimit::TotalLimit { limit } => if placed.iter().sum::<usize>() >= *limit {
+                return Err(PlacementError::TotalLimitReached);
+            },
+            PieceLimit::TypeCountLimit { limits } => if placed[piece] >= limits[piece] {
+                return Err(PlacementError::TypeLimitReached(piece));
+            },
+            PieceLimit::PointLimit { point_values, point_limit } => {
+                if state.placed_points(ruleset, seat) + point_values[piece] > *point_limit {
+                    return Err(PlacementError::PointLimitReached);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Whether `piece` fits in what is left of the current turn.
+fn fits_turn(ruleset: &Ruleset, alternation_type: AlternationType, state: &PlacementState, piece: usize) -> bool {
+    match alternation_type {
+        AlternationType::TurnsPoints { per_turn_points, hard_limit: true } => state.placed_this_turn + ruleset.piece_value(piece) <= per_turn_points,
+        _ => true,
+    }
+}
+
+/// Whether `seat` can place any piece anywhere.
+fn can_place_any(ruleset: &Ruleset, board: &Board, state: &PlacementState, seat: u64, placement_area: &PlacementArea) -> bool {
+    let any_piece = (0..ruleset.pieces.len()).any(|piece| check_piece_limits(ruleset, state, seat, piece).is_ok());
+    any_piece && placement_area.seat_area(ruleset, seat).into_iter()
+        .any(|coordinate| board.in_bounds(coordinate) && ruleset.board_type.get_space(coordinate) != Space::Invalid && board.get(coordinate).is_none())
+}
+
+/// Marks seats that cannot place as finished then picks the seat to place next.
+fn advance(ruleset: &Ruleset, board: &mut Board) {
+    let (first_seat, alternation_type, placement_area, _) = match placement_rules(ruleset) {
+        None => return,
+        Some(rules) => rules,
+    };
+    let mut state = match board.placement() {
+        None => return,
+        Some(state) => state.clone(),
+    };
+    for seat in 0..ruleset.seats {
+        if !state.is_finished(seat) && !can_place_any(ruleset, board, &state, seat, placement_area) {
+            state.finished[seat as usize] = true;
+        }
+    }
+    if state.finished.iter().all(|&finished| finished) {
+        board.set_placement(None);
+        board.set_current_seat(first_seat);
+        return;
+    }
+
+    let current = board.current_seat();
+    let continues = !state.is_finished(current) && match alternation_type {
+        AlternationType::TurnsCount { per_turn_count } => state.placed_this_turn < per_turn_count,
+        AlternationType::TurnsPoints { per_turn_points, hard_limit } => state.placed_this_turn < per_turn_points
+            && (!hard_limit || (0..ruleset.pieces.len()).any(|piece| check_piece_limits(ruleset, &state, current, piece).is_ok() && fits_turn(ruleset, alternation_type, &state, piece))),
+        AlternationType::Points => false,
+        AlternationType::WholePlacement | AlternationType::Hidden => true,
+    };
+    let next = if continues {
+        current
+    } else {
+        state.placed_this_turn = 0;
+        match alternation_type {
+            AlternationType::Points => {
+                // Lowest points first, ties go to the seat closest after first_seat
+                (0..ruleset.seats)
+                    .map(|offset| (first_seat + offset) % ruleset.seats)
+                    .filter(|&seat| !state.is_finished(seat))
+                    .min_by_key(|&seat| state.placed_points(ruleset, seat))
+                    .unwrap_or(first_seat)
+            }
+            _ => (1..=ruleset.seats)
+                .map(|offset| (current + offset) % ruleset.seats)
+                .find(|&seat| !state.is_finished(seat))
+                .unwrap_or(first_seat),
+        }
+    };
+    board.set_current_seat(next);
+    board.set_placement(Some(state));
+}
+
+pub type PlacementResult<T> = Result<T, PlacementError>;
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum PlacementError {
+    NotPlacementPhase,
+    HiddenPlacement,
+    OutsideArea(Coordinate),
+    TotalLimitReached,
+    TypeLimitReached(usize),
+    PointLimitReached,
+    TurnPointsExceeded,
+    NotHiddenPlacement,
+    AlreadySubmitted(u64),
+    WaitingForCommitments,
+    CommitmentMismatch,
+    BoardError(BoardError),
+}
+impl Display for PlacementError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        <Self as Debug>::fmt(self, f)
+    }
+}
+impl Error for PlacementError {
+    fn cause(&self) -> Option<&dyn Error> {
+        match self {
+            Self::BoardError(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+impl From<BoardError> for PlacementError {
+    fn from(from: BoardError) -> Self {
+        Self::BoardError(from)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use crate::game::board::Board;
+    use crate::game::Coordinate;
+    use crate::game::placement::{finish_placement, place_piece, placement_moves, PlacementError};
+    use crate::game::ruleset::Ruleset;
+    use crate::game::ruleset::standard::standard_rules;
+    use crate::game::ruleset::starting_positions::alteration_type::AlternationType;
+    use crate::game::ruleset::starting_positions::piece_limit::PieceLimit;
+    use crate::game::ruleset::starting_positions::placement_area::PlacementArea;
+    use crate::game::ruleset::starting_positions::StartingPositions;
+
+    /// Seat 0 places on row 1 and seat 1 on row 10, columns 0 to 3.
+    fn placement_rules(alternation_type: AlternationType, piece_limits: Vec<PieceLimit>) -> Ruleset {
+        let mut ruleset = standard_rules();
+        let area: HashSet<_> = (0..4).map(|column| Coordinate::new(1, column)).collect();
+        ruleset.starting_positions = StartingPositions::Placement {
+            first_seat: 1,
+            alternation_type,
+            placement_area: PlacementArea::MirroredFlipped(area),
+            piece_limits: piece_limits.into_iter().collect(),
+        };
+        ruleset.verify().expect("Invalid placement ruleset");
+        ruleset
+    }
+
+    #[test]
+    fn turns_count() {
+        let ruleset = placement_rules(AlternationType::TurnsCount { per_turn_count: 2 }, vec![PieceLimit::TotalLimit { limit: 3 }]);
+        let mut board = Board::new(&ruleset);
+        assert_eq!(board.current_seat(), 1);
+        assert_eq!(place_piece(&ruleset, &mut board, Coordinate::new(1, 0), 0), Err(PlacementError::OutsideArea(Coordinate::new(1, 0))));
+        place_piece(&ruleset, &mut board, Coordinate::new(10, 0), 0).unwrap();
+        assert_eq!(board.current_seat(), 1);
+        place_piece(&ruleset, &mut board, Coordinate::new(10, 1), 1).unwrap();
+        assert_eq!(board.current_seat(), 0);
+        place_piece(&ruleset, &mut board, Coordinate::new(1, 0), 0).unwrap();
+        place_piece(&ruleset, &mut board, Coordinate::new(1, 1), 0).unwrap();
+        assert_eq!(board.current_seat(), 1);
+        place_piece(&ruleset, &mut board, Coordinate::new(10, 2), 0).unwrap();
+        // Seat 1 hit the total limit
+        assert_eq!(board.current_seat(), 0);
+        place_piece(&ruleset, &mut board, Coordinate::new(1, 2), 0).unwrap();
+        assert!(board.placement().is_none());
+        assert_eq!(board.current_seat(), 1);
+        assert_eq!(board.pieces().count(), 6);
+    }
+
+    #[test]
+    fn turns_points_hard_limit() {
+        let point_limit = PieceLimit::PointLimit { point_values: vec![3, 1], point_limit: 5 };
+        let ruleset = placement_rules(AlternationType::TurnsPoints { per_turn_points: 2, hard_limit: true }, vec![point_limit]);
+        let mut board = Board::new(&ruleset);
+        assert_eq!(place_piece(&ruleset, &mut board, Coordinate::new(10, 0), 0), Err(PlacementError::TurnPointsExceeded));
+        assert!(placement_moves(&ruleset, &board).iter().all(|&(_, piece)| piece == 1));
+        place_piece(&ruleset, &mut board, Coordinate::new(10, 0), 1).unwrap();
+        place_piece(&ruleset, &mut board, Coordinate::new(10, 1), 1).unwrap();
+        assert_eq!(board.current_seat(), 0);
+    }
+
+    #[test]
+    fn points_lowest_first() {
+        let point_limit = PieceLimit::PointLimit { point_values: vec![3, 1], point_limit: 4 };
+        let ruleset = placement_rules(AlternationType::Points, vec![point_limit]);
+        let mut board = Board::new(&ruleset);
+        place_piece(&ruleset, &mut board, Coordinate::new(10, 0), 0).unwrap();
+        assert_eq!(board.current_seat(), 0);
+        place_piece(&ruleset, &mut board, Coordinate::new(1, 0), 1).unwrap();
+        assert_eq!(board.current_seat(), 0);
+        place_piece(&ruleset, &mut board, Coordinate::new(1, 1), 1).unwrap();
+        assert_eq!(board.current_seat(), 0);
+        place_piece(&ruleset, &mut board, Coordinate::new(1, 2), 1).unwrap();
+        // Tied at 3, seat 1 places first
+        assert_eq!(board.current_seat(), 1);
+    }
+
+    #[test]
+    fn whole_placement() {
+        let ruleset = placement_rules(AlternationType::WholePlacement, vec![PieceLimit::TypeCountLimit { limits: vec![1, 2] }]);
+        let mut board = Board::new(&ruleset);
+        place_piece(&ruleset, &mut board, Coordinate::new(10, 0), 1).unwrap();
+        assert_eq!(board.current_seat(), 1);
+        finish_placement(&ruleset, &mut board).unwrap();
+        assert_eq!(board.current_seat(), 0);
+        place_piece(&ruleset, &mut board, Coordinate::new(1, 0), 0).unwrap();
+        assert_eq!(place_piece(&ruleset, &mut board, Coordinate::new(1, 1), 0), Err(PlacementError::TypeLimitReached(0)));
+        place_piece(&ruleset, &mut board, Coordinate::new(1, 1), 1).unwrap();
+        place_piece(&ruleset, &mut board, Coordinate::new(1, 2), 1).unwrap();
+        assert!(board.placement().is_none());
+    }
+}
@@ -0,0 +1,265 @@
+use crate::game::board::{Board, BoardPiece};
+use crate::game::Coordinate;
+use crate::game::hidden_placement::{HiddenPiece, HiddenSubmission};
+use crate::game::notation::{format_coordinate, format_letters, NotationError, NotationResult, parse_coordinate, parse_letters};
+use crate::game::placement::PlacementState;
+use crate::game::ruleset::Ruleset;
+use crate::game::ruleset::starting_positions::StartingPositions;
+
+/// Writes a board as a position string, read it back with `parse_position` and the same ruleset.
+///
+/// The string is five space separated fields:
+/// - Rows from the top separated by `/`. Each piece is its piece index as upper case letters followed by its seat as
+///   lower case letters, `Ab` is piece 0 of seat 1. A number is that many empty or invalid spaces.
+/// - The seat to move.
+/// - The number of completed turns.
+/// - Piece indexes captured from each seat separated by `,`, seats separated by `/`, `-` if none.
+/// - `-` once placement is over, otherwise the pieces placed this turn, `:`, then each seat separated by `/`.
+///   A seat is its placed count of each piece separated by `,`, then `!` if it has finished, then its hidden
+///   submission: nothing if waiting, `#` and the hex commitment, `=` and the locked placements like `0@a1,1@b1`,
+///   or `?` if withheld.
+///
+/// The standard ruleset with one piece each looks like `10/10/Aa9/.../9Bb/10 0 0 -/- -`.
+pub fn format_position(board: &Board) -> String {
+    let rows: Vec<_> = (0..board.rows())
+        .map(|row| {
+            let mut out = String::new();
+            let mut empty = 0;
+            for column in 0..board.columns() {
+                match board.get(Coordinate::new(row as i16, column as i16)) {
+                    None => empty += 1,
+                    Some(piece) => {
+                        if empty > 0 {
+                            out += &empty.to_string();
+                            empty = 0;
+                        }
+                        out += &format_letters(piece.piece, b'A');
+                        out += &format_letters(piece.seat as usize, b'a');
+                    }
+                }
+            }
+            if empty > 0 {
+                out += &empty.to_string();
+            }
+            out
+        })
+        .collect();
+    let captured: Vec<_> = (0..board.seats())
+        .map(|seat| join_or_dash(board.captured(seat).iter().map(ToString::to_string)))
+        .collect();
+    let placement = match board.placement() {
+        None => "-".to_string(),
+        Some(state) => {
+            let seats: Vec<_> = (0..board.seats())
+                .map(|seat| {
+                    let counts: Vec<_> = state.placed(seat).iter().map(ToString::to_string).collect();
+                    let finished = if state.is_finished(seat) { "!" } else { "" };
+                    let hidden = match &state.hidden()[seat as usize] {
+                        HiddenSubmission::Waiting => String::new(),
+                        HiddenSubmission::Committed(hash) => format!("#{}", hash.iter().map(|byte| format!("{:02x}", byte)).collect::<String>()),
+                        HiddenSubmission::Locked(pieces) => format!("={}", pieces.iter().map(|piece| format!("{}@{}", piece.piece, format_coordinate(piece.coordinate))).collect::<Vec<_>>().join(",")),
+                        HiddenSubmission::Withheld => "?".to_string(),
+                    };
+                    format!("{}{}{}", counts.join(","), finished, hidden)
+                })
+                .collect();
+            format!("{}:{}", state.placed_this_turn(), seats.join("/"))
+        }
+    };
+    format!("{} {} {} {} {}", rows.join("/"), board.current_seat(), board.turn_number(), captured.join("/"), placement)
+}
+
+/// Reads a position string written by `format_position`, checking it against the ruleset.
+pub fn parse_position(ruleset: &Ruleset, text: &str) -> NotationResult<Board> {
+    let invalid = || NotationError::InvalidPosition(text.to_string());
+    let fields: Vec<_> = text.split_whitespace().collect();
+    if fields.len() != 5 {
+        return Err(invalid());
+    }
+    let mut board = Board::new(ruleset);
+
+    let rows: Vec<_> = fields[0].split('/').collect();
+    if rows.len() != board.rows() {
+        return Err(invalid());
+    }
+    for (row, cells) in rows.into_iter().enumerate() {
+        let mut column = 0;
+        let mut rest = cells;
+        while !rest.is_empty() {
+            let digits = leading(rest, |c| c.is_ascii_digit());
+            if !digits.is_empty() {
+                column += digits.parse::<usize>().map_err(|_| invalid())?;
+                rest = &rest[digits.len()..];
+                continue;
+            }
+            let piece_letters = leading(rest, |c| c.is_ascii_uppercase());
+            let seat_letters = leading(&rest[piece_letters.len()..], |c| c.is_ascii_lowercase());
+            let piece = parse_letters(piece_letters, b'A').ok_or_else(invalid)?;
+            let seat = parse_letters(seat_letters, b'a').ok_or_else(invalid)?;
+            board.place(ruleset, Coordinate::new(row as i16, column as i16), BoardPiece::new(seat as u64, piece))?;
+            rest = &rest[piece_letters.len() + seat_letters.len()..];
+            column += 1;
+        }
+        if column != board.columns() {
+            return Err(invalid());
+        }
+    }
+
+    let current_seat: u64 = fields[1].parse().map_err(|_| invalid())?;
+    if current_seat >= board.seats() {
+        return Err(invalid());
+    }
+    board.set_current_seat(current_seat);
+    board.set_turn_number(fields[2].parse().map_err(|_| invalid())?);
+
+    let captured: Vec<_> = fields[3].split('/').collect();
+    if captured.len() as u64 != board.seats() {
+        return Err(invalid());
+    }
+    for (seat, pieces) in captured.into_iter().enumerate() {
+        let pieces = split_or_dash(pieces).map(str::parse).collect::<Result<Vec<usize>, _>>().map_err(|_| invalid())?;
+        if pieces.iter().any(|&piece| ruleset.get_piece(piece).is_none()) {
+            return Err(invalid());
+        }
+        board.set_captured(seat as u64, pieces);
+    }
+
+    if fields[4] == "-" {
+        board.set_placement(None);
+    } else if let StartingPositions::Placement { .. } = ruleset.starting_positions {
+        board.set_placement(Some(parse_placement(ruleset, fields[4]).ok_or_else(invalid)?));
+    } else {
+        return Err(invalid());
+    }
+    Ok(board)
+}
+
+fn parse_placement(ruleset: &Ruleset, text: &str) -> Option<PlacementState> {
+    let index = text.find(':')?;
+    let placed_this_turn = text[..index].parse().ok()?;
+    let seats: Vec<_> = text[index + 1..].split('/').collect();
+    if seats.len() as u64 != ruleset.seats {
+        return None;
+    }
+    let mut placed = Vec::new();
+    let mut finished = Vec::new();
+    let mut hidden = Vec::new();
+    for seat in seats {
+        let counts_text = leading(seat, |c| c.is_ascii_digit() || c == ',');
+        let counts = counts_text.split(',').map(str::parse).collect::<Result<Vec<usize>, _>>().ok()?;
+        if counts.len() != ruleset.pieces.len() {
+            return None;
+        }
+        placed.push(counts);
+        let rest = &seat[counts_text.len()..];
+        let rest = match rest.strip_prefix('!') {
+            None => {
+                finished.push(false);
+                rest
+            }
+            Some(rest) => {
+                finished.push(true);
+                rest
+            }
+        };
+        hidden.push(match rest.chars().next() {
+            None => HiddenSubmission::Waiting,
+            Some('?') if rest.len() == 1 => HiddenSubmission::Withheld,
+            Some('#') => HiddenSubmission::Committed(parse_hex(&rest[1..])?),
+            Some('=') => HiddenSubmission::Locked(split_or_empty(&rest[1..])
+                .map(|place| {
+                    let index = place.find('@')?;
+                    Some(HiddenPiece { coordinate: parse_coordinate(&place[index + 1..]).ok()?, piece: place[..index].parse().ok()? })
+                })
+                .collect::<Option<_>>()?),
+            Some(_) => return None,
+        });
+    }
+    Some(PlacementState::from_parts(placed, placed_this_turn, finished, hidden))
+}
+
+/// The longest prefix of `text` where every character matches.
+fn leading(text: &str, matches: impl Fn(char) -> bool) -> &str {
+    &text[..text.find(|c| !matches(c)).unwrap_or(text.len())]
+}
+fn join_or_dash(items: impl Iterator<Item=String>) -> String {
+    let items: Vec<_> = items.collect();
+    if items.is_empty() {
+        "-".to_string()
+    } else {
+        items.join(",")
+    }
+}
+fn split_or_dash(text: &str) -> impl Iterator<Item=&str> {
+    text.split(',').filter(move |_| text != "-")
+}
+fn split_or_empty(text: &str) -> impl Iterator<Item=&str> {
+    text.split(',').filter(move |_| !text.is_empty())
+}
+fn parse_hex(text: &str) -> Option<Vec<u8>> {
+    text.as_bytes().chunks(2)
+        .map(|pair| std::str::from_utf8(pair).ok().filter(|pair| pair.len() == 2).and_then(|pair| u8::from_str_radix(pair, 16).ok()))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use crate::game::board::{Board, BoardError, BoardPiece};
+    use crate::game::capture::apply_move;
+    use crate::game::Coordinate;
+    use crate::game::hidden_placement::{commit_hidden_placement, HiddenPiece, lock_hidden_placement};
+    use crate::game::movement::legal_moves;
+    use crate::game::notation::NotationError;
+    use crate::game::position::{format_position, parse_position};
+    use crate::game::ruleset::standard::standard_rules;
+    use crate::game::ruleset::starting_positions::alteration_type::AlternationType;
+    use crate::game::ruleset::starting_positions::placement_area::PlacementArea;
+    use crate::game::ruleset::starting_positions::StartingPositions;
+
+    #[test]
+    fn round_trip() {
+        let ruleset = standard_rules();
+        let mut board = Board::new(&ruleset);
+        board.place(&ruleset, Coordinate::new(2, 0), BoardPiece::new(0, 0)).unwrap();
+        board.place(&ruleset, Coordinate::new(9, 9), BoardPiece::new(1, 1)).unwrap();
+        assert_eq!(format_position(&board), "10/10/Aa9/10/10/10/10/10/10/9Bb/10/10 0 0 -/- -");
+
+        let mut board = Board::initial(&ruleset).unwrap();
+        for _ in 0..8 {
+            let action = legal_moves(&ruleset, &board, board.current_seat()).remove(0);
+            apply_move(&ruleset, &mut board, &action).unwrap();
+            let text = format_position(&board);
+            assert_eq!(parse_position(&ruleset, &text), Ok(board.clone()));
+        }
+    }
+
+    #[test]
+    fn hidden_placement_round_trip() {
+        let mut ruleset = standard_rules();
+        let area: HashSet<_> = (0..10).map(|column| Coordinate::new(5, column)).collect();
+        ruleset.starting_positions = StartingPositions::Placement {
+            first_seat: 0,
+            alternation_type: AlternationType::Hidden,
+            placement_area: PlacementArea::NonMirrored(vec![area.clone(), area]),
+            piece_limits: HashSet::new(),
+        };
+        let mut board = Board::new(&ruleset);
+        commit_hidden_placement(&ruleset, &mut board, 0, vec![0, 171, 255]).unwrap();
+        lock_hidden_placement(&ruleset, &mut board, 1, vec![HiddenPiece { coordinate: Coordinate::new(5, 1), piece: 1 }], None).unwrap();
+        let text = format_position(&board);
+        assert!(text.ends_with(" 0:0,0#00abff/0,0=1@b5"));
+        assert_eq!(parse_position(&ruleset, &text), Ok(board));
+    }
+
+    #[test]
+    fn invalid_positions() {
+        let ruleset = standard_rules();
+        // Row 0 only has goals in the middle
+        assert_eq!(parse_position(&ruleset, "Aa9/10/10/10/10/10/10/10/10/10/10/10 0 0 -/- -"), Err(NotationError::BoardError(BoardError::InvalidSpace(Coordinate::new(0, 0)))));
+        for &text in &["10/10 0 0 -/- -", "11/10/10/10/10/10/10/10/10/10/10/10 0 0 -/- -", "10/10/10/10/10/10/10/10/10/10/10/10 2 0 -/- -", "10/10/10/10/10/10/10/10/10/10/10/10 0 0 - -", "10/10/10/10/10/10/10/10/10/10/10/10 0 0 -/- 0:0,0/0,0"] {
+            assert_eq!(parse_position(&ruleset, text), Err(NotationError::InvalidPosition(text.to_string())));
+        }
+    }
+}
@@ -7,6 +7,7 @@ use std::ops::Index;
 use std::vec::Vec;
 
 use ndarray::Array2;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::game::coordinate::Coordinate;
@@ -18,7 +19,7 @@ use crate::game::ruleset::Ruleset;
 pub mod space;
 
 /// A board definition
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub enum BoardType {
     /// Rectangular board of size (rows, columns) with goals in columns defined by goal_locations.
     /// All goal locations must be < columns.
@@ -26,54 +27,77 @@ pub enum BoardType {
     /// Only valid for two seats
     Rectangular {
         /// Must be >= 1 and <= `u8::max_value() - 2`.
+        #[schemars(range(min = 1, max = 253))]
         rows: u8,
         /// Must be >= 2.
+        #[schemars(range(min = 2))]
         columns: u8,
         /// All must be < columns.
         goal_locations: Vec<u8>,
     },
     /// Custom board definition.
-    Custom(Array2<Space>),
+    Custom(#[schemars(with = "SpaceGrid")] Array2<Space>),
+}
+/// How ndarray serializes `Array2<Space>`, for the schema of `BoardType::Custom`.
+#[derive(JsonSchema)]
+#[allow(dead_code)]
+struct SpaceGrid {
+    /// Serialization version, always 1
+    #[schemars(range(min = 1, max = 1))]
+    v: u8,
+    /// `[rows, columns]`, both must be <= `u8::max_value()`.
+    #[schemars(length(equal = 2))]
+    dim: Vec<usize>,
+    /// Spaces row by row, `rows * columns` of them
+    data: Vec<Space>,
 }
 impl BoardType {
     pub fn verify(&self, ruleset: &Ruleset) -> BoardTypeVerifyResult<()> {
+        let mut first = None;
+        self.verify_each(ruleset, &mut |_, error| {
+            first.get_or_insert(error);
+        });
+        first.map_or(Ok(()), Err)
+    }
+    /// Calls `report` for every problem found with its JSON pointer from the board type, like
+    /// `/Rectangular/goal_locations/1`.
+    pub fn verify_each(&self, ruleset: &Ruleset, report: &mut dyn FnMut(String, BoardTypeVerifyError)) {
         match self {
             BoardType::Rectangular {
                 rows,
                 columns,
                 goal_locations,
             } => {
-                if ruleset.seats != 2{
-                    return Err(InvalidSeatCount(ruleset.seats))
+                if ruleset.seats != 2 {
+                    report(String::new(), InvalidSeatCount(ruleset.seats));
                 }
                 if *rows < 1 || *rows > u8::max_value() - 2 {
-                    Err(BoardTypeVerifyError::InvalidRows(*rows as usize))
-                } else if *columns < 2 {
-                    Err(BoardTypeVerifyError::InvalidColumns(*columns as usize))
-                } else {
-                    for &location in goal_locations {
-                        if location >= *columns {
-                            return Err(BoardTypeVerifyError::InvalidGoalLocation(location as usize));
-                        }
+                    report("/Rectangular/rows".to_string(), InvalidRows(*rows as usize));
+                }
+                if *columns < 2 {
+                    report("/Rectangular/columns".to_string(), InvalidColumns(*columns as usize));
+                }
+                for (index, &location) in goal_locations.iter().enumerate() {
+                    if location >= *columns {
+                        report(format!("/Rectangular/goal_locations/{}", index), InvalidGoalLocation(location as usize));
                     }
-                    Ok(())
                 }
             }
             BoardType::Custom(board) => {
                 if board.nrows() > u8::max_value() as usize {
-                    return Err(BoardTypeVerifyError::InvalidRows(board.nrows()));
+                    report("/Custom".to_string(), InvalidRows(board.nrows()));
                 }
                 if board.ncols() > u8::max_value() as usize {
-                    return Err(BoardTypeVerifyError::InvalidColumns(board.ncols()));
+                    report("/Custom".to_string(), InvalidColumns(board.ncols()));
                 }
-                for space in board{
-                    if let Goal(seat) = space{
-                        if *seat >= ruleset.seats{
-                            return Err(InvalidGoalSeat(*space));
+                // Serialized boards keep their spaces in row major order under `data`
+                for (index, space) in board.iter().enumerate() {
+                    if let Goal(seat) = space {
+                        if *seat >= ruleset.seats {
+                            report(format!("/Custom/data/{}", index), InvalidGoalSeat(*space));
                         }
                     }
                 }
-                Ok(())
             }
         }
     }
@@ -85,7 +109,7 @@ impl BoardType {
                 columns,
                 goal_locations,
             } => {
-                if coordinate.row >= (rows + 2) as i16 || coordinate.column >= *columns as i16 {
+                if coordinate.row < 0 || coordinate.column < 0 || coordinate.row >= (rows + 2) as i16 || coordinate.column >= *columns as i16 {
                     Space::Invalid
                 } else if coordinate.row == 0 || coordinate.row == (rows + 1) as i16 {
                     if goal_locations.contains(&(coordinate.column as u8)) {
@@ -102,7 +126,7 @@ impl BoardType {
                 }
             }
             BoardType::Custom(board) => {
-                if coordinate.row >= board.nrows() as i16 || coordinate.column >= board.ncols() as i16 {
+                if coordinate.row < 0 || coordinate.column < 0 || coordinate.row >= board.nrows() as i16 || coordinate.column >= board.ncols() as i16 {
                     Space::Invalid
                 } else {
                     *board.index(coordinate.to_tuple())
@@ -145,6 +169,18 @@ pub enum BoardTypeVerifyError {
     InvalidGoalSeat(Space),
     InvalidSeatCount(u64),
 }
+impl BoardTypeVerifyError {
+    /// Name of the error variant.
+    pub fn code(&self) -> &'static str {
+        match self {
+            BoardTypeVerifyError::InvalidRows(_) => "InvalidRows",
+            BoardTypeVerifyError::InvalidColumns(_) => "InvalidColumns",
+            BoardTypeVerifyError::InvalidGoalLocation(_) => "InvalidGoalLocation",
+            BoardTypeVerifyError::InvalidGoalSeat(_) => "InvalidGoalSeat",
+            BoardTypeVerifyError::InvalidSeatCount(_) => "InvalidSeatCount",
+        }
+    }
+}
 impl Display for BoardTypeVerifyError {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         <Self as Debug>::fmt(self, f)
@@ -2,9 +2,10 @@ use std::convert::TryFrom;
 use std::num::ParseIntError;
 use std::str::FromStr;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize, )]
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize, JsonSchema)]
 pub struct Color{
     /// represented as `rgba(r, g, b, a)`
     color: String,
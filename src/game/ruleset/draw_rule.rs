@@ -0,0 +1,91 @@
+use core::cmp::{Eq, PartialEq};
+use core::hash::{Hash, Hasher};
+use core::mem::discriminant;
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::fmt;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// How a game ends without a winner, or what happens when a seat is stuck.
+///
+/// Hash, Eq, and PartialEq are based on the discriminate.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub enum DrawRule {
+    /// The game is drawn once the same position with the same seat to move occurs this many times.
+    /// Must be >= 2.
+    Repetition(#[schemars(range(min = 2))] usize),
+    /// The game is drawn after this many turns in a row without a capture.
+    /// Must be > 0.
+    NoCapture(#[schemars(range(min = 1))] usize),
+    /// What happens when the seat to move has no legal move.
+    /// Without this rule the game is drawn.
+    NoLegalMove(StalemateOutcome),
+}
+impl DrawRule {
+    /// Name of the variant, a ruleset has at most one rule of each.
+    pub fn name(&self) -> &'static str {
+        match self {
+            DrawRule::Repetition(_) => "Repetition",
+            DrawRule::NoCapture(_) => "NoCapture",
+            DrawRule::NoLegalMove(_) => "NoLegalMove",
+        }
+    }
+    pub fn verify(&self) -> DrawRuleResult<()> {
+        match self {
+            DrawRule::Repetition(count) => if *count < 2 {
+                return Err(DrawRuleError::RepetitionCountTooLow(*count));
+            },
+            DrawRule::NoCapture(turns) => if *turns == 0 {
+                return Err(DrawRuleError::NoCaptureTurnsIs0);
+            },
+            DrawRule::NoLegalMove(_) => {}
+        }
+        Ok(())
+    }
+}
+impl Hash for DrawRule {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        discriminant(self).hash(state);
+    }
+}
+impl PartialEq for DrawRule {
+    fn eq(&self, other: &Self) -> bool {
+        discriminant(self).eq(&discriminant(other))
+    }
+}
+impl Eq for DrawRule {}
+
+/// The result when the seat to move has no legal move.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize, JsonSchema)]
+pub enum StalemateOutcome {
+    /// Every seat draws.
+    Draw,
+    /// The stuck seat's team loses.
+    Loss,
+    /// The stuck seat's team wins.
+    Win,
+}
+
+pub type DrawRuleResult<T> = Result<T, DrawRuleError>;
+#[derive(Copy, Clone, Debug)]
+pub enum DrawRuleError {
+    RepetitionCountTooLow(usize),
+    NoCaptureTurnsIs0,
+}
+impl DrawRuleError {
+    /// Name of the error variant.
+    pub fn code(&self) -> &'static str {
+        match self {
+            DrawRuleError::RepetitionCountTooLow(_) => "RepetitionCountTooLow",
+            DrawRuleError::NoCaptureTurnsIs0 => "NoCaptureTurnsIs0",
+        }
+    }
+}
+impl Display for DrawRuleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        <Self as Debug>::fmt(self, f)
+    }
+}
+impl Error for DrawRuleError {}
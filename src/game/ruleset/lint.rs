@@ -0,0 +1,237 @@
+use std::collections::{HashSet, VecDeque};
+use std::fmt;
+use std::fmt::{Debug, Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::Coordinate;
+use crate::game::direction::{Direction, Directions};
+use crate::game::ruleset::board_type::space::Space;
+use crate::game::ruleset::piece_definition::{CaptureRule, CaptureTarget, JumpLimit, MoveRule, PieceDefinition};
+use crate::game::ruleset::Ruleset;
+use crate::game::ruleset::starting_positions::StartingPositions;
+use crate::game::ruleset::victory_condition::VictoryCondition;
+
+/// A problem with a ruleset that still verifies, such as a rule that can never apply.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct RulesetWarning {
+    /// JSON pointer to the part of the ruleset the warning is for, like `/pieces/0`, victory conditions are named by
+    /// variant like `/victory_conditions/GoalCount`
+    pub path: String,
+    pub kind: RulesetWarningKind,
+}
+impl Display for RulesetWarning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {:?}", self.path, self.kind)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum RulesetWarningKind {
+    /// The piece has no move rule and cannot jump
+    PieceCannotAct,
+    /// No piece counting for the goal condition can reach a goal of the team from where it starts
+    GoalUnreachable {
+        team: Vec<u64>,
+    },
+    /// The placement area includes a space that is invalid or off the board
+    PlacementOnInvalidSpace(Coordinate),
+    /// The ally set has a seat that is not in the game
+    AllySeatOutOfRange(u64),
+    /// The capture rule can never be used by the piece
+    CaptureNeverTriggers(CaptureRule),
+}
+
+/// Finds problems `Ruleset::verify` does not check, every problem found is returned.
+///
+/// Only meaningful for a ruleset that verifies, nothing is returned unless `Ruleset::shape_verifies`.
+pub fn lint(ruleset: &Ruleset) -> Vec<RulesetWarning> {
+    let mut out = Vec::new();
+    if !ruleset.shape_verifies() {
+        return out;
+    }
+    let mut warn = |path: String, kind| out.push(RulesetWarning { path, kind });
+
+    for (index, set) in ruleset.allies.iter().enumerate() {
+        let mut seats: Vec<_> = set.iter().copied().filter(|&seat| seat >= ruleset.seats).collect();
+        seats.sort_unstable();
+        for seat in seats {
+            warn(format!("/allies/{}", index), RulesetWarningKind::AllySeatOutOfRange(seat));
+        }
+    }
+
+    let single_team = ruleset.teams().len() < 2;
+    for (index, piece) in ruleset.pieces.iter().enumerate() {
+        let can_jump = piece.jump_limit != JumpLimit::Cannot;
+        let can_move = piece.move_rule != MoveRule::None;
+        if !can_jump && !can_move {
+            warn(format!("/pieces/{}", index), RulesetWarningKind::PieceCannotAct);
+        }
+        let mut rules: Vec<_> = piece.capture_rules.iter().collect();
+        rules.sort_by_key(|(&rule, _)| rule as u8);
+        for (&rule, &target) in rules {
+            let usable = match rule {
+                CaptureRule::JumpOver | CaptureRule::JumpOn => can_jump,
+                CaptureRule::Move => can_move,
+            };
+            if !usable || (single_team && target == CaptureTarget::EnemyOnly) {
+                warn(format!("/pieces/{}/capture_rules/{:?}", index, rule), RulesetWarningKind::CaptureNeverTriggers(rule));
+            }
+        }
+    }
+
+    for condition in &ruleset.victory_conditions {
+        if let VictoryCondition::GoalCount { valid_pieces, .. } = condition {
+            for team in ruleset.teams() {
+                if !goal_reachable(ruleset, &team, valid_pieces) {
+                    warn(format!("/victory_conditions/{}", condition.name()), RulesetWarningKind::GoalUnreachable { team });
+                }
+            }
+        }
+    }
+
+    if let StartingPositions::Placement { placement_area, .. } = &ruleset.starting_positions {
+        let mut invalid: Vec<_> = (0..ruleset.seats)
+            .flat_map(|seat| placement_area.seat_area(ruleset, seat))
+            .filter(|&coordinate| ruleset.board_type.get_space(coordinate) == Space::Invalid)
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        invalid.sort_by_key(|coordinate| (coordinate.row, coordinate.column));
+        for coordinate in invalid {
+            warn("/starting_positions/Placement/placement_area".to_string(), RulesetWarningKind::PlacementOnInvalidSpace(coordinate));
+        }
+    }
+    out
+}
+
+/// Whether any piece in `valid_pieces` of a seat in `team` can get from where it starts to a goal of the team.
+///
+/// Other pieces are ignored, so jumps are allowed anywhere two spaces away in a jump direction.
+fn goal_reachable(ruleset: &Ruleset, team: &[u64], valid_pieces: &HashSet<usize>) -> bool {
+    let seat_positions = ruleset.starting_positions.seat_positions(ruleset);
+    for &seat in team {
+        for &piece in valid_pieces {
+            let definition = match ruleset.get_piece(piece) {
+                None => continue,
+                Some(definition) => definition,
+            };
+            let starts: Vec<_> = match (&seat_positions, &ruleset.starting_positions) {
+                (Some(positions), _) => positions.get(seat as usize)
+                    .and_then(|pieces| pieces.get(piece))
+                    .cloned()
+                    .unwrap_or_default(),
+                (None, StartingPositions::Placement { placement_area, .. }) => placement_area.seat_area(ruleset, seat).into_iter().collect(),
+                (None, _) => Vec::new(),
+            };
+            let found = reachable(ruleset, definition, starts).into_iter()
+                .any(|coordinate| matches!(ruleset.board_type.get_space(coordinate), Space::Goal(goal) if team.contains(&goal)));
+            if found {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// Every valid space the piece can get to from `starts` over any number of turns.
+fn reachable(ruleset: &Ruleset, piece: &PieceDefinition, starts: Vec<Coordinate>) -> HashSet<Coordinate> {
+    let mut offsets = Vec::new();
+    match piece.move_rule {
+        MoveRule::SameDirection { directions, .. } | MoveRule::AnyDirection { directions, .. } => {
+            offsets.extend(direction_list(directions).map(|direction| direction.offset()));
+        }
+        MoveRule::None => {}
+    }
+    match piece.jump_limit {
+        JumpLimit::Unlimited { directions } | JumpLimit::Limited { directions, .. } => {
+            offsets.extend(direction_list(directions).map(|direction| direction.offset() + direction.offset()));
+        }
+        JumpLimit::Cannot => {}
+    }
+
+    let mut found: HashSet<_> = starts.iter().copied().filter(|&start| ruleset.board_type.get_space(start) != Space::Invalid).collect();
+    let mut queue: VecDeque<_> = found.iter().copied().collect();
+    while let Some(position) = queue.pop_front() {
+        for &offset in &offsets {
+            let next = position + offset;
+            if ruleset.board_type.get_space(next) != Space::Invalid && found.insert(next) {
+                queue.push_back(next);
+            }
+        }
+    }
+    found
+}
+
+fn direction_list(directions: Directions) -> impl Iterator<Item=Direction> {
+    Vec::<Direction>::from(directions).into_iter()
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use crate::game::Coordinate;
+    use crate::game::direction::Directions;
+    use crate::game::ruleset::lint::{RulesetWarning, RulesetWarningKind};
+    use crate::game::ruleset::piece_definition::{CaptureRule, JumpLimit, MoveRule};
+    use crate::game::ruleset::standard::standard_rules;
+    use crate::game::ruleset::starting_positions::alteration_type::AlternationType;
+    use crate::game::ruleset::starting_positions::placement_area::PlacementArea;
+    use crate::game::ruleset::starting_positions::StartingPositions;
+
+    fn warning(path: &str, kind: RulesetWarningKind) -> RulesetWarning {
+        RulesetWarning { path: path.to_string(), kind }
+    }
+
+    #[test]
+    fn standard_is_clean() {
+        assert_eq!(standard_rules().lint(), Vec::new());
+    }
+
+    #[test]
+    fn pieces() {
+        let mut ruleset = standard_rules();
+        ruleset.pieces[1].move_rule = MoveRule::None;
+        ruleset.pieces[1].jump_limit = JumpLimit::Cannot;
+        ruleset.allies = vec![vec![0, 1, 4].into_iter().collect()];
+        assert_eq!(ruleset.lint(), vec![
+            warning("/allies/0", RulesetWarningKind::AllySeatOutOfRange(4)),
+            warning("/pieces/0/capture_rules/JumpOver", RulesetWarningKind::CaptureNeverTriggers(CaptureRule::JumpOver)),
+            warning("/pieces/1", RulesetWarningKind::PieceCannotAct),
+            warning("/pieces/1/capture_rules/JumpOver", RulesetWarningKind::CaptureNeverTriggers(CaptureRule::JumpOver)),
+        ]);
+    }
+
+    #[test]
+    fn unreachable_goal() {
+        let mut ruleset = standard_rules();
+        // Only sideways moves, seat 0 can never leave its rows
+        for piece in &mut ruleset.pieces {
+            piece.move_rule = MoveRule::AnyDirection { limit: 1, directions: Directions::EAST | Directions::WEST };
+            piece.jump_limit = JumpLimit::Unlimited { directions: Directions::EAST | Directions::WEST };
+        }
+        let warnings = ruleset.lint();
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings.iter().all(|warning| warning.path == "/victory_conditions/GoalCount"));
+        assert_eq!(warnings[0].kind, RulesetWarningKind::GoalUnreachable { team: vec![0] });
+        assert_eq!(warnings[1].kind, RulesetWarningKind::GoalUnreachable { team: vec![1] });
+    }
+
+    #[test]
+    fn placement_area() {
+        let mut ruleset = standard_rules();
+        let area: HashSet<_> = vec![Coordinate::new(0, 0), Coordinate::new(3, 3), Coordinate::new(20, 1)].into_iter().collect();
+        ruleset.starting_positions = StartingPositions::Placement {
+            first_seat: 0,
+            alternation_type: AlternationType::WholePlacement,
+            placement_area: PlacementArea::NonMirrored(vec![area, vec![Coordinate::new(8, 3)].into_iter().collect()]),
+            piece_limits: HashSet::new(),
+        };
+        let path = "/starting_positions/Placement/placement_area";
+        assert_eq!(ruleset.lint(), vec![
+            warning(path, RulesetWarningKind::PlacementOnInvalidSpace(Coordinate::new(0, 0))),
+            warning(path, RulesetWarningKind::PlacementOnInvalidSpace(Coordinate::new(20, 1))),
+        ]);
+    }
+}
@@ -0,0 +1,122 @@
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Debug, Display, Formatter};
+
+use serde_json::{Map, Value};
+
+use crate::game::ruleset::Ruleset;
+
+/// Schema version of rulesets serialized by this build.
+///
+/// Bump it and add a migration to `MIGRATIONS` whenever the serialized shape of `Ruleset` changes.
+/// 1. The first rulesets, without `draw_rules`
+/// 2. Added `draw_rules`
+pub const RULESET_VERSION: u32 = 2;
+
+/// Upgrades a serialized ruleset by one version in place.
+type Migration = fn(&mut Map<String, Value>) -> MigrationResult<()>;
+/// Migration `i` upgrades a ruleset from version `i + 1` to version `i + 2`.
+const MIGRATIONS: [Migration; RULESET_VERSION as usize - 1] = [
+    add_draw_rules,
+];
+
+/// Deserializes a ruleset saved as version `version`, migrating it to `RULESET_VERSION` first.
+pub fn load_ruleset(json: &str, version: u32) -> MigrationResult<Ruleset> {
+    let value = migrate(serde_json::from_str(json)?, version)?;
+    Ok(serde_json::from_value(value)?)
+}
+
+/// Upgrades a serialized ruleset from `version` to `RULESET_VERSION`.
+pub fn migrate(mut value: Value, version: u32) -> MigrationResult<Value> {
+    if version == 0 || version > RULESET_VERSION {
+        return Err(MigrationError::UnknownVersion(version));
+    }
+    let object = match value.as_object_mut() {
+        None => return Err(MigrationError::NotAnObject(version)),
+        Some(object) => object,
+    };
+    for migration in &MIGRATIONS[version as usize - 1..] {
+        migration(object)?;
+    }
+    Ok(value)
+}
+
+/// 1 to 2, rulesets without draw rules get none.
+fn add_draw_rules(ruleset: &mut Map<String, Value>) -> MigrationResult<()> {
+    ruleset.entry("draw_rules").or_insert_with(|| Value::Array(Vec::new()));
+    Ok(())
+}
+
+pub type MigrationResult<T> = Result<T, MigrationError>;
+#[derive(Clone, Debug)]
+pub enum MigrationError {
+    /// Older than the first version or newer than `RULESET_VERSION`
+    UnknownVersion(u32),
+    /// The ruleset of this version is not a json object
+    NotAnObject(u32),
+    SerdeJsonError(String),
+}
+impl Display for MigrationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        <Self as Debug>::fmt(self, f)
+    }
+}
+impl Error for MigrationError {}
+impl From<serde_json::Error> for MigrationError {
+    fn from(from: serde_json::Error) -> Self {
+        Self::SerdeJsonError(from.to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use serde_json::Value;
+
+    use crate::game::ruleset::migration::{load_ruleset, migrate, MigrationError, RULESET_VERSION};
+    use crate::game::ruleset::standard::standard_rules;
+
+    fn read(path: &str) -> Value {
+        serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    /// Every fixture in `example/migration`, named `v<version>_<ruleset>.json`, must load and migrate to the fixture of
+    /// the same ruleset at `RULESET_VERSION`.
+    #[test]
+    fn fixtures() {
+        let mut count = 0;
+        for entry in fs::read_dir("example/migration").unwrap() {
+            let path = entry.unwrap().path();
+            let file_name = path.file_name().unwrap().to_str().unwrap();
+            let rest = file_name.strip_prefix('v').unwrap();
+            let split = rest.find('_').unwrap();
+            let (version, name) = (&rest[..split], &rest[split + 1..]);
+            let version: u32 = version.parse().unwrap();
+            let text = fs::read_to_string(&path).unwrap();
+
+            let migrated = migrate(serde_json::from_str(&text).unwrap(), version).unwrap();
+            assert_eq!(migrated, read(&format!("example/migration/v{}_{}", RULESET_VERSION, name)), "{}", file_name);
+            load_ruleset(&text, version).unwrap();
+            count += 1;
+        }
+        assert!(count >= 4);
+    }
+
+    #[test]
+    fn current_is_unchanged() {
+        let json = serde_json::to_string(&standard_rules()).unwrap();
+        let value: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(migrate(value.clone(), RULESET_VERSION).unwrap(), value);
+        load_ruleset(&json, RULESET_VERSION).unwrap().verify().unwrap();
+    }
+
+    #[test]
+    fn unknown_versions() {
+        let json = serde_json::to_string(&standard_rules()).unwrap();
+        assert!(matches!(load_ruleset(&json, 0), Err(MigrationError::UnknownVersion(0))));
+        assert!(matches!(load_ruleset(&json, RULESET_VERSION + 1), Err(MigrationError::UnknownVersion(_))));
+        assert!(matches!(load_ruleset("[]", 1), Err(MigrationError::NotAnObject(1))));
+        assert!(matches!(load_ruleset("{", 1), Err(MigrationError::SerdeJsonError(_))));
+    }
+}
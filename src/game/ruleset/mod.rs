@@ -3,30 +3,41 @@ use std::collections::HashSet;
 use std::error::Error;
 use std::fmt::{Debug, Display, Formatter};
 
+use schemars::{JsonSchema, schema_for};
+use schemars::schema::RootSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::game::ruleset::board_type::{BoardType, BoardTypeVerifyError};
 use crate::game::ruleset::color::Color;
+use crate::game::ruleset::draw_rule::{DrawRule, DrawRuleError};
+use crate::game::ruleset::lint::{lint, RulesetWarning};
 use crate::game::ruleset::piece_definition::{PieceDefinition, PieceDefinitionError};
+use crate::game::ruleset::report::VerificationReport;
 use crate::game::ruleset::RulesetError::{DuplicateColor, DuplicateSeatInAllies, SeatsCountInvalid};
 use crate::game::ruleset::starting_positions::{StartingPositions, StartingPositionsError};
+use crate::game::ruleset::starting_positions::piece_limit::PieceLimit;
 use crate::game::ruleset::victory_condition::{VictoryCondition, VictoryConditionError};
 
 pub mod starting_positions;
 pub mod board_type;
 
 pub mod color;
+pub mod draw_rule;
+pub mod lint;
+pub mod migration;
 pub mod piece_definition;
+pub mod report;
 pub mod shape;
 pub mod standard;
 pub mod victory_condition;
 
 /// The ruleset for a game of Kapto
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct Ruleset {
     /// The name of the ruleset
     pub name: String,
-    /// Number of seats in the game
+    /// Number of seats in the game, must be >= 2
+    #[schemars(range(min = 2))]
     pub seats: u64,
     /// Allied seats, a single seat may not appear more than once
     pub allies: Vec<HashSet<u64>>,
@@ -41,60 +52,150 @@ pub struct Ruleset {
     /// How to win the game
     /// At least one must be set
     pub victory_conditions: HashSet<VictoryCondition>,
+    /// How the game can end without a winner
+    #[serde(default)]
+    pub draw_rules: HashSet<DrawRule>,
 }
 impl Ruleset {
     pub fn verify(&self) -> RulesetResult<()> {
+        let mut first = None;
+        self.verify_each(&mut |_, error| {
+            first.get_or_insert(error);
+        });
+        first.map_or(Ok(()), Err)
+    }
+    /// Calls `report` for every problem found with its JSON pointer, like `/pieces/1/jump_limit`.
+    ///
+    /// Problems are found in the same order as `verify`, so the first one reported is the error `verify` returns.
+    /// Starting positions and victory conditions are only checked once the seats, seat colors, and board type verify,
+    /// as they rely on them.
+    pub fn verify_each(&self, report: &mut dyn FnMut(String, RulesetError)) {
+        let mut shape_valid = true;
+
         // Verify seats
-        if self.seats < 2 {
-            return Err(SeatsCountInvalid(self.seats));
-        }
+        self.verify_seats(&mut |path, error| {
+            shape_valid = false;
+            report(path, error);
+        });
 
         // Verify allies
         let mut allies_found = HashSet::new();
-        for set in &self.allies {
-            for &seat in set {
+        for (index, set) in self.allies.iter().enumerate() {
+            let mut seats: Vec<_> = set.iter().copied().collect();
+            seats.sort_unstable();
+            for seat in seats {
                 if !allies_found.insert(seat) {
-                    return Err(DuplicateSeatInAllies(seat));
+                    report(format!("/allies/{}", index), DuplicateSeatInAllies(seat));
                 }
             }
         }
 
         // Verify seat_colors
-        if self.seats != self.seat_colors.len() as u64 {
-            return Err(RulesetError::NotEnoughColorsSet(self.seat_colors.len()));
-        }
-        let mut colors = HashSet::with_capacity(self.seats as usize);
-        for color in &self.seat_colors {
-            if !colors.insert(color) {
-                return Err(DuplicateColor(color.clone()));
-            }
-        }
+        self.verify_seat_colors(&mut |path, error| {
+            shape_valid = false;
+            report(path, error);
+        });
 
         // Verify pieces
         let mut pieces_set = HashSet::with_capacity(self.pieces.len());
-        for piece in self.pieces.iter() {
-            piece.verify()?;
+        for (index, piece) in self.pieces.iter().enumerate() {
+            piece.verify_each(&mut |path, error| report(format!("/pieces/{}{}", index, path), error.into()));
             if !pieces_set.insert(piece) {
-                return Err(RulesetError::PieceDuplicated(piece.clone()));
+                report(format!("/pieces/{}/name", index), RulesetError::PieceDuplicated(piece.clone()));
             }
         }
 
         // Verify board_type
-        self.board_type.verify(self)?;
+        self.board_type.verify_each(self, &mut |path, error| {
+            shape_valid = false;
+            report(format!("/board_type{}", path), error.into());
+        });
 
-        // Verify starting_positions
-        self.starting_positions.verify(self)?;
+        if shape_valid {
+            // Verify starting_positions
+            self.starting_positions.verify_each(self, &mut |path, error| report(format!("/starting_positions{}", path), error.into()));
 
-        // Verify victory_conditions
-        for victory_condition in self.victory_conditions.iter() {
-            victory_condition.verify(self)?;
+            // Verify victory_conditions
+            for victory_condition in &self.victory_conditions {
+                if let Err(error) = victory_condition.verify(self) {
+                    report(format!("/victory_conditions/{}", victory_condition.name()), error.into());
+                }
+            }
+        }
+
+        // Verify draw_rules
+        for draw_rule in &self.draw_rules {
+            if let Err(error) = draw_rule.verify() {
+                report(format!("/draw_rules/{}", draw_rule.name()), error.into());
+            }
         }
-        Ok(())
+    }
+    /// Whether the seats, seat colors, and board type verify, the checks every other check relies on.
+    pub fn shape_verifies(&self) -> bool {
+        let mut valid = true;
+        self.verify_seats(&mut |_, _| valid = false);
+        self.verify_seat_colors(&mut |_, _| valid = false);
+        self.board_type.verify_each(self, &mut |_, _| valid = false);
+        valid
+    }
+    fn verify_seats(&self, report: &mut dyn FnMut(String, RulesetError)) {
+        if self.seats < 2 {
+            report("/seats".to_string(), SeatsCountInvalid(self.seats));
+        }
+    }
+    fn verify_seat_colors(&self, report: &mut dyn FnMut(String, RulesetError)) {
+        if self.seats != self.seat_colors.len() as u64 {
+            report("/seat_colors".to_string(), RulesetError::NotEnoughColorsSet(self.seat_colors.len()));
+        }
+        let mut colors = HashSet::with_capacity(self.seat_colors.len());
+        for (index, color) in self.seat_colors.iter().enumerate() {
+            if !colors.insert(color) {
+                report(format!("/seat_colors/{}", index), DuplicateColor(color.clone()));
+            }
+        }
+    }
+    /// Every problem `verify` can find, see `report::VerificationReport`.
+    pub fn verify_all(&self) -> VerificationReport {
+        VerificationReport::new(self)
+    }
+    /// JSON Schema of serialized rulesets and every type in them, doc comments become descriptions.
+    pub fn json_schema() -> RootSchema {
+        schema_for!(Ruleset)
+    }
+    /// Warnings for rules that verify but can never apply, see `lint::lint`.
+    pub fn lint(&self) -> Vec<RulesetWarning> {
+        lint(self)
     }
 
     pub fn get_piece(&self, index: usize) -> Option<&PieceDefinition> {
         self.pieces.get(index)
     }
+    /// Seats grouped by alliance, seats without allies are alone, ordered by lowest seat.
+    pub fn teams(&self) -> Vec<Vec<u64>> {
+        let mut out: Vec<Vec<u64>> = Vec::new();
+        for seat in 0..self.seats {
+            match out.iter_mut().find(|team| self.are_allied(team[0], seat)) {
+                None => out.push(vec![seat]),
+                Some(team) => team.push(seat),
+            }
+        }
+        out
+    }
+    /// The point value of a piece, taken from `PieceLimit::PointLimit` if set and 1 otherwise.
+    pub fn piece_value(&self, index: usize) -> usize {
+        if let StartingPositions::Placement { piece_limits, .. } = &self.starting_positions {
+            for piece_limit in piece_limits {
+                if let PieceLimit::PointLimit { point_values, .. } = piece_limit {
+                    return point_values.get(index).copied().unwrap_or(1);
+                }
+            }
+        }
+        1
+    }
+    /// Whether two seats are on the same side, a seat is always allied with itself.
+    pub fn are_allied(&self, seat: u64, other: u64) -> bool {
+        seat == other || self.allies.iter().any(|set| set.contains(&seat) && set.contains(&other))
+    }
 }
 pub type RulesetResult<T> = Result<T, RulesetError>;
 #[derive(Clone, Debug)]
@@ -108,6 +209,24 @@ pub enum RulesetError {
     BoardTypeVerifyError(BoardTypeVerifyError),
     StartingPositionsError(StartingPositionsError),
     VictoryConditionError(VictoryConditionError),
+    DrawRuleError(DrawRuleError),
+}
+impl RulesetError {
+    /// Name of the error variant, or the code of the error it wraps.
+    pub fn code(&self) -> &'static str {
+        match self {
+            RulesetError::SeatsCountInvalid(_) => "SeatsCountInvalid",
+            RulesetError::DuplicateSeatInAllies(_) => "DuplicateSeatInAllies",
+            RulesetError::NotEnoughColorsSet(_) => "NotEnoughColorsSet",
+            RulesetError::DuplicateColor(_) => "DuplicateColor",
+            RulesetError::PieceDuplicated(_) => "PieceDuplicated",
+            RulesetError::PieceDefinitionError(error) => error.code(),
+            RulesetError::BoardTypeVerifyError(error) => error.code(),
+            RulesetError::StartingPositionsError(error) => error.code(),
+            RulesetError::VictoryConditionError(error) => error.code(),
+            RulesetError::DrawRuleError(error) => error.code(),
+        }
+    }
 }
 impl Display for RulesetError {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
@@ -126,6 +245,7 @@ impl Error for RulesetError {
             Self::BoardTypeVerifyError(error) => Some(error),
             Self::StartingPositionsError(error) => Some(error),
             Self::VictoryConditionError(error) => Some(error),
+            Self::DrawRuleError(error) => Some(error),
         }
     }
 }
@@ -149,3 +269,58 @@ impl From<VictoryConditionError> for RulesetError {
         Self::VictoryConditionError(from)
     }
 }
+impl From<DrawRuleError> for RulesetError {
+    fn from(from: DrawRuleError) -> Self {
+        Self::DrawRuleError(from)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::Value;
+
+    use crate::game::ruleset::{Ruleset, RulesetError};
+    use crate::game::ruleset::board_type::BoardType;
+    use crate::game::ruleset::board_type::BoardTypeVerifyError;
+    use crate::game::ruleset::standard::standard_rules;
+
+    #[test]
+    fn json_schema() {
+        let schema = serde_json::to_value(Ruleset::json_schema()).unwrap();
+        assert_eq!(schema["title"], "Ruleset");
+        assert_eq!(schema["properties"]["seats"]["minimum"], 2.0);
+        assert_eq!(schema["properties"]["seats"]["description"], "Number of seats in the game, must be >= 2");
+
+        let rectangular = &schema["definitions"]["BoardType"]["oneOf"][0]["properties"]["Rectangular"]["properties"];
+        assert_eq!(rectangular["rows"]["minimum"], 1.0);
+        assert_eq!(rectangular["rows"]["maximum"], 253.0);
+        assert_eq!(rectangular["columns"]["minimum"], 2.0);
+        for name in &["PieceDefinition", "StartingPositions", "VictoryCondition", "DrawRule", "PlacementArea", "Directions"] {
+            assert!(schema["definitions"][name].is_object(), "{}", name);
+        }
+
+        let standard = serde_json::to_value(standard_rules()).unwrap();
+        let properties = schema["properties"].as_object().unwrap();
+        assert!(standard.as_object().unwrap().keys().all(|key| properties.contains_key(key)));
+        assert_eq!(schema["required"].as_array().unwrap().len(), properties.len() - 1);
+        assert!(!schema["required"].as_array().unwrap().contains(&Value::from("draw_rules")));
+    }
+
+    #[test]
+    fn too_many_rows() {
+        let mut ruleset = standard_rules();
+        ruleset.board_type = BoardType::Rectangular { rows: 255, columns: 10, goal_locations: vec![4, 5] };
+        assert!(matches!(ruleset.verify(), Err(RulesetError::BoardTypeVerifyError(BoardTypeVerifyError::InvalidRows(255)))));
+        assert_eq!(ruleset.verify_all().errors.len(), 1);
+        assert!(ruleset.lint().is_empty());
+    }
+
+    #[test]
+    fn too_many_seats() {
+        let mut ruleset = standard_rules();
+        ruleset.seats = u64::MAX;
+        assert!(matches!(ruleset.verify(), Err(RulesetError::NotEnoughColorsSet(2))));
+        assert!(!ruleset.verify_all().is_valid());
+        assert!(ruleset.lint().is_empty());
+    }
+}
@@ -5,6 +5,7 @@ use std::fmt::{Debug, Formatter};
 use std::fmt::Display;
 use std::hash::{Hash, Hasher};
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::game::direction::Directions;
@@ -14,7 +15,7 @@ use crate::game::ruleset::shape::Shape;
 /// Defines a piece
 ///
 /// Hash, Eq, and PartialEq are only defined for `name`
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct PieceDefinition {
     /// The name of the piece type
     pub name: String,
@@ -38,12 +39,23 @@ pub struct PieceDefinition {
 }
 impl PieceDefinition {
     pub fn verify(&self) -> PieceDefinitionResult<()> {
+        let mut first = None;
+        self.verify_each(&mut |_, error| {
+            first.get_or_insert(error);
+        });
+        first.map_or(Ok(()), Err)
+    }
+    /// Calls `report` for every problem found with its JSON pointer from the piece, like `/jump_limit`.
+    pub fn verify_each(&self, report: &mut dyn FnMut(String, PieceDefinitionError)) {
         if self.name.is_empty() {
-            return Err(PieceDefinitionError::NameInvalid(self.name.clone()));
+            report("/name".to_string(), PieceDefinitionError::NameInvalid(self.name.clone()));
+        }
+        if let Err(error) = self.jump_limit.verify() {
+            report("/jump_limit".to_string(), error.into());
+        }
+        if let Err(error) = self.move_rule.verify() {
+            report("/move_rule".to_string(), error.into());
         }
-        self.jump_limit.verify()?;
-        self.move_rule.verify()?;
-        Ok(())
     }
 }
 impl Hash for PieceDefinition {
@@ -65,6 +77,16 @@ pub enum PieceDefinitionError {
     JumpLimitError(JumpLimitError),
     MoveRuleError(MoveRuleError),
 }
+impl PieceDefinitionError {
+    /// Name of the error variant, or the code of the error it wraps.
+    pub fn code(&self) -> &'static str {
+        match self {
+            PieceDefinitionError::NameInvalid(_) => "NameInvalid",
+            PieceDefinitionError::JumpLimitError(error) => error.code(),
+            PieceDefinitionError::MoveRuleError(error) => error.code(),
+        }
+    }
+}
 impl Display for PieceDefinitionError {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         <Self as Debug>::fmt(self, f)
@@ -91,7 +113,7 @@ impl From<MoveRuleError> for PieceDefinitionError {
 }
 
 /// The rule for how jumps can happen
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize, JsonSchema)]
 pub enum JumpRule {
     /// A piece may not go to any previous space within the same jump
     NoBacktracking,
@@ -101,7 +123,7 @@ pub enum JumpRule {
     Open,
 }
 /// The rule for how captures can happen
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize, JsonSchema)]
 pub enum CaptureRule {
     /// Can capture by jumping over
     JumpOver,
@@ -111,7 +133,7 @@ pub enum CaptureRule {
     Move,
 }
 /// The rule for when captures happen during
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize, JsonSchema)]
 pub enum CaptureTimingRule {
     /// Pieces are removed after they are jumped over, an enemy piece cannot be jumped twice
     AfterJump,
@@ -119,7 +141,7 @@ pub enum CaptureTimingRule {
     AfterTurn,
 }
 /// The rule for what can get captured
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize, JsonSchema)]
 pub enum CaptureTarget {
     /// Captures only enemy pieces
     EnemyOnly,
@@ -129,7 +151,7 @@ pub enum CaptureTarget {
     All,
 }
 /// The rule for when this piece is forced to capture
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize, JsonSchema)]
 pub enum CaptureRequirement {
     /// Must capture if possible, higher values mean this piece is forced before others
     Forced(isize),
@@ -137,7 +159,7 @@ pub enum CaptureRequirement {
     None,
 }
 /// The rule for how a piece jumps
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize, JsonSchema)]
 pub enum JumpLimit {
     /// Piece can jump an unlimited number of times
     Unlimited {
@@ -145,6 +167,8 @@ pub enum JumpLimit {
     },
     /// Piece can jump a limited number of times
     Limited {
+        /// Must be > 0
+        #[schemars(range(min = 1))]
         limit: usize,
         directions: Directions,
     },
@@ -174,6 +198,15 @@ pub enum JumpLimitError {
     NoDirectionsSet,
     LimitedTo0,
 }
+impl JumpLimitError {
+    /// Name of the error variant.
+    pub fn code(&self) -> &'static str {
+        match self {
+            JumpLimitError::NoDirectionsSet => "NoDirectionsSet",
+            JumpLimitError::LimitedTo0 => "LimitedTo0",
+        }
+    }
+}
 impl Display for JumpLimitError {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         <Self as Debug>::fmt(self, f)
@@ -182,15 +215,19 @@ impl Display for JumpLimitError {
 impl Error for JumpLimitError {}
 
 /// The rule for how this piece moves
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize, JsonSchema)]
 pub enum MoveRule {
     /// Piece can move in any one direction from the vec up to the limit amount
     SameDirection {
+        /// Must be > 0
+        #[schemars(range(min = 1))]
         limit: usize,
         directions: Directions,
     },
     /// Piece can move in any direction from the vec up to the limit amount
     AnyDirection {
+        /// Must be > 0
+        #[schemars(range(min = 1))]
         limit: usize,
         directions: Directions,
     },
@@ -219,6 +256,15 @@ pub enum MoveRuleError {
     NoDirectionsSet,
     LimitedTo0,
 }
+impl MoveRuleError {
+    /// Name of the error variant.
+    pub fn code(&self) -> &'static str {
+        match self {
+            MoveRuleError::NoDirectionsSet => "NoDirectionsSet",
+            MoveRuleError::LimitedTo0 => "LimitedTo0",
+        }
+    }
+}
 impl Display for MoveRuleError {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         <Self as Debug>::fmt(self, f)
@@ -227,7 +273,7 @@ impl Display for MoveRuleError {
 impl Error for MoveRuleError {}
 
 /// The rule for what movement is allowed while in a goal
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize, JsonSchema)]
 pub enum GoalMovementRule {
     /// Piece is locked in place once it gets to the goal
     Locked,
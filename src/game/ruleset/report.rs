@@ -0,0 +1,135 @@
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::ruleset::piece_definition::PieceDefinitionError;
+use crate::game::ruleset::{Ruleset, RulesetError};
+use crate::game::ruleset::starting_positions::StartingPositionsError;
+
+/// Every problem that stops a ruleset from verifying, for showing them all at once.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct VerificationReport {
+    /// In the order `Ruleset::verify` finds them
+    pub errors: Vec<VerificationIssue>,
+}
+impl VerificationReport {
+    pub fn new(ruleset: &Ruleset) -> Self {
+        let mut errors = Vec::new();
+        ruleset.verify_each(&mut |path, error| errors.push(VerificationIssue::new(path, &error)));
+        Self { errors }
+    }
+
+    /// Whether the ruleset verifies.
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+impl Display for VerificationReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for issue in &self.errors {
+            writeln!(f, "{}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+/// One verification problem.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct VerificationIssue {
+    /// JSON pointer to the serialized field with the problem, like `/pieces/1/jump_limit`
+    ///
+    /// Entries of `victory_conditions` and `draw_rules` are named by variant, like `/draw_rules/NoCapture`, as the
+    /// sets have no fixed order and hold at most one of each variant.
+    pub path: String,
+    /// Name of the most specific error variant, like `LimitedTo0`, see `RulesetError::code`
+    pub code: String,
+    /// The most specific error with its details
+    pub message: String,
+}
+impl VerificationIssue {
+    pub fn new(path: String, error: &RulesetError) -> Self {
+        Self { path, code: error.code().to_string(), message: innermost(error).to_string() }
+    }
+}
+impl Display for VerificationIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// The error that caused `error`, skipping errors that only wrap another.
+fn innermost(error: &RulesetError) -> &dyn Display {
+    match error {
+        RulesetError::PieceDefinitionError(PieceDefinitionError::JumpLimitError(error)) => error,
+        RulesetError::PieceDefinitionError(PieceDefinitionError::MoveRuleError(error)) => error,
+        RulesetError::PieceDefinitionError(error) => error,
+        RulesetError::BoardTypeVerifyError(error) => error,
+        RulesetError::StartingPositionsError(StartingPositionsError::AlterationTypeError(error)) => error,
+        RulesetError::StartingPositionsError(StartingPositionsError::PlacementAreaError(error)) => error,
+        RulesetError::StartingPositionsError(StartingPositionsError::PieceLimitError(error)) => error,
+        RulesetError::StartingPositionsError(error) => error,
+        RulesetError::VictoryConditionError(error) => error,
+        RulesetError::DrawRuleError(error) => error,
+        error => error,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::game::Coordinate;
+    use crate::game::direction::Directions;
+    use crate::game::ruleset::board_type::BoardType;
+    use crate::game::ruleset::draw_rule::DrawRule;
+    use crate::game::ruleset::piece_definition::{JumpLimit, MoveRule};
+    use crate::game::ruleset::report::VerificationReport;
+    use crate::game::ruleset::standard::standard_rules;
+    use crate::game::ruleset::starting_positions::StartingPositions;
+
+    #[test]
+    fn standard_is_valid() {
+        let report = standard_rules().verify_all();
+        assert!(report.is_valid());
+        assert_eq!(report, VerificationReport::default());
+    }
+
+    #[test]
+    fn every_error() {
+        let mut ruleset = standard_rules();
+        ruleset.allies = vec![vec![0].into_iter().collect(), vec![0, 1].into_iter().collect()];
+        ruleset.pieces[0].jump_limit = JumpLimit::Limited { limit: 0, directions: Directions::NORTH };
+        ruleset.pieces[1].move_rule = MoveRule::AnyDirection { limit: 1, directions: Directions::NONE };
+        ruleset.pieces[1].name = ruleset.pieces[0].name.clone();
+        if let StartingPositions::NotMirrored(positions) = &mut ruleset.starting_positions {
+            positions[1][1][0] = Coordinate::new(0, 0);
+            positions[1][1][1] = positions[0][0][0];
+        }
+        ruleset.draw_rules = vec![DrawRule::NoCapture(0)].into_iter().collect();
+
+        let report = ruleset.verify_all();
+        let found: Vec<_> = report.errors.iter().map(|issue| (issue.path.as_str(), issue.code.as_str())).collect();
+        assert_eq!(found, vec![
+            ("/allies/1", "DuplicateSeatInAllies"),
+            ("/pieces/0/jump_limit", "LimitedTo0"),
+            ("/pieces/1/move_rule", "NoDirectionsSet"),
+            ("/pieces/1/name", "PieceDuplicated"),
+            ("/starting_positions/NotMirrored/1/1/0", "InvalidPositionForBoard"),
+            ("/starting_positions/NotMirrored/1/1/1", "DuplicatePosition"),
+            ("/draw_rules/NoCapture", "NoCaptureTurnsIs0"),
+        ]);
+        assert_eq!(report.errors[1].message, "LimitedTo0");
+        assert_eq!(report.errors[0].code, ruleset.verify().unwrap_err().code());
+
+        let json = serde_json::to_string(&report).unwrap();
+        assert_eq!(serde_json::from_str::<VerificationReport>(&json).unwrap(), report);
+    }
+
+    #[test]
+    fn board_paths() {
+        let mut ruleset = standard_rules();
+        ruleset.seats = 3;
+        ruleset.board_type = BoardType::Rectangular { rows: 10, columns: 1, goal_locations: vec![0, 4] };
+        let paths: Vec<_> = ruleset.verify_all().errors.into_iter().map(|issue| issue.path).collect();
+        assert_eq!(&paths[..4], &["/seat_colors", "/board_type", "/board_type/Rectangular/columns", "/board_type/Rectangular/goal_locations/1"]);
+    }
+}
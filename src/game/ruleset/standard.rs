@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::game::Coordinate;
 use crate::game::direction::Directions;
@@ -12,11 +12,12 @@ use crate::game::ruleset::victory_condition::VictoryCondition;
 
 // use ndarray::array;
 
+/// The standard rules, with no draw rules like `example/standard_rules.json`.
 pub fn standard_rules() -> Ruleset {
     Ruleset {
         name: "Standard".to_string(),
         seats: 2,
-        allies: vec![vec![0, 1].into_iter().collect()],
+        allies: vec![],
         seat_colors: vec![ColorBuiltIn::Red.into(), ColorBuiltIn::Blue.into()],
         pieces: get_pieces(),
         board_type: get_board(),
@@ -28,6 +29,7 @@ pub fn standard_rules() -> Ruleset {
                 valid_pieces: vec![0, 1].into_iter().collect(),
             }
         ].into_iter().collect(),
+        draw_rules: HashSet::new(),
     }
 }
 
@@ -4,21 +4,25 @@ use std::fmt::{Debug, Formatter};
 use std::fmt;
 use std::fmt::Display;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::game::ruleset::starting_positions::piece_limit::PieceLimit;
 
 /// The alteration for placement
-#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub enum AlternationType {
     /// Players alternate placing per_turn_count pieces.
     TurnsCount {
         /// Must be > 0
+        #[schemars(range(min = 1))]
         per_turn_count: usize
     },
     /// Players alternate placing per_turn_points points.
     /// Requires piece_limits to contain a point limit.
     TurnsPoints {
+        /// Must be > 0
+        #[schemars(range(min = 1))]
         per_turn_points: usize,
         hard_limit: bool,
     },
@@ -58,6 +62,16 @@ pub enum AlterationTypeError {
     PerTurnPointsIs0,
     NoPointLimitForTurnsPoints,
 }
+impl AlterationTypeError {
+    /// Name of the error variant.
+    pub fn code(&self) -> &'static str {
+        match self {
+            AlterationTypeError::CountIs0 => "CountIs0",
+            AlterationTypeError::PerTurnPointsIs0 => "PerTurnPointsIs0",
+            AlterationTypeError::NoPointLimitForTurnsPoints => "NoPointLimitForTurnsPoints",
+        }
+    }
+}
 impl Display for AlterationTypeError {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         <Self as Debug>::fmt(self, f)
@@ -6,11 +6,13 @@ use core::result::Result;
 use std::collections::HashSet;
 use std::error::Error;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use placement_area::PlacementArea;
 
 use crate::game::coordinate::{Coordinate, flip_coordinate, rotate_coordinate};
+use crate::game::ruleset::board_type::BoardType;
 use crate::game::ruleset::board_type::space::Space;
 use crate::game::ruleset::piece_definition::PieceDefinition;
 use crate::game::ruleset::Ruleset;
@@ -23,7 +25,7 @@ pub mod piece_limit;
 pub mod placement_area;
 
 /// Defines the starting positions
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub enum StartingPositions {
     /// Mirrored start positions, only defines a single side.
     /// Mirror will flip about horizontal center.
@@ -51,154 +53,148 @@ pub enum StartingPositions {
     },
 }
 impl StartingPositions {
-    fn verify_mirrored_flipped(piece_positions: &[Vec<Coordinate>], ruleset: &Ruleset) -> StartingPositionsResult<()> {
+    /// Verifies mirrored positions, `mirror` gives seat 1's copy of a position.
+    fn verify_mirrored(piece_positions: &[Vec<Coordinate>], mirror: fn(&BoardType, Coordinate) -> Coordinate, path: &str, ruleset: &Ruleset, report: &mut dyn FnMut(String, StartingPositionsError)) {
         // Tracks already used positions
         let mut found = HashSet::new();
         for (piece_index, positions) in piece_positions.iter().enumerate() {
             let piece = match ruleset.get_piece(piece_index) {
-                None => return Err(StartingPositionsError::PieceIndexNotFound(piece_index)),
+                None => {
+                    report(format!("{}/{}", path, piece_index), StartingPositionsError::PieceIndexNotFound(piece_index));
+                    continue;
+                }
                 Some(piece) => piece,
             };
-            for &position in positions {
+            for (index, &position) in positions.iter().enumerate() {
+                let path = format!("{}/{}/{}", path, piece_index, index);
                 // Check already used positions and add to list
                 if !found.insert(position) {
-                    return Err(StartingPositionsError::DuplicatePosition {
+                    report(path, StartingPositionsError::DuplicatePosition {
                         piece: piece.clone(),
                         position,
                     });
+                    continue;
                 }
 
-                match ruleset.board_type.get_space(position) {
-                    Space::Normal => {}
-                    space => {
-                        return Err(StartingPositionsError::InvalidPositionForBoard {
-                            space,
-                            piece: piece.clone(),
-                            position,
-                        });
-                    }
-                }
-                match ruleset.board_type.get_space(flip_coordinate(&ruleset.board_type, position)) {
-                    Space::Normal => {}
-                    space => {
-                        return Err(StartingPositionsError::InvalidPositionForBoard {
-                            space,
-                            piece: piece.clone(),
-                            position,
-                        });
-                    }
-                }
-            }
-        }
-        Ok(())
-    }
-    fn verify_mirrored_rotated(piece_positions: &[Vec<Coordinate>], ruleset: &Ruleset) -> StartingPositionsResult<()> {
-        // Tracks already used positions
-        let mut found = HashSet::new();
-        for (piece_index, positions) in piece_positions.iter().enumerate() {
-            let piece = match ruleset.get_piece(piece_index) {
-                None => return Err(StartingPositionsError::PieceIndexNotFound(piece_index)),
-                Some(piece) => piece,
-            };
-            for &position in positions {
-                // Check already used positions and add to list
-                if !found.insert(position) {
-                    return Err(StartingPositionsError::DuplicatePosition {
+                let invalid = [position, mirror(&ruleset.board_type, position)].iter()
+                    .map(|&position| ruleset.board_type.get_space(position))
+                    .find(|&space| space != Space::Normal);
+                if let Some(space) = invalid {
+                    report(path, StartingPositionsError::InvalidPositionForBoard {
+                        space,
                         piece: piece.clone(),
                         position,
                     });
                 }
-
-                match ruleset.board_type.get_space(position) {
-                    Space::Normal => {}
-                    space => {
-                        return Err(StartingPositionsError::InvalidPositionForBoard {
-                            space,
-                            piece: piece.clone(),
-                            position,
-                        });
-                    }
-                }
-                match ruleset.board_type.get_space(rotate_coordinate(&ruleset.board_type, position)) {
-                    Space::Normal => {}
-                    space => {
-                        return Err(StartingPositionsError::InvalidPositionForBoard {
-                            space,
-                            piece: piece.clone(),
-                            position,
-                        });
-                    }
-                }
             }
         }
-        Ok(())
     }
-    fn verify_not_mirrored(seat_piece_positions: &[Vec<Vec<Coordinate>>], ruleset: &Ruleset) -> StartingPositionsResult<()> {
-        if seat_piece_positions.len() as u64 != ruleset.seats{
-            return Err(StartingPositionsError::SeatNumberDoesNotMatch(seat_piece_positions.len()))
+    fn verify_not_mirrored(seat_piece_positions: &[Vec<Vec<Coordinate>>], path: &str, ruleset: &Ruleset, report: &mut dyn FnMut(String, StartingPositionsError)) {
+        if seat_piece_positions.len() as u64 != ruleset.seats {
+            report(path.to_string(), StartingPositionsError::SeatNumberDoesNotMatch(seat_piece_positions.len()));
         }
 
         // Tracks already used positions
         let mut found = HashSet::new();
-        for piece_positions in seat_piece_positions {
+        for (seat, piece_positions) in seat_piece_positions.iter().enumerate() {
             for (piece_index, positions) in piece_positions.iter().enumerate() {
                 let piece = match ruleset.get_piece(piece_index) {
-                    None => return Err(StartingPositionsError::PieceIndexNotFound(piece_index)),
+                    None => {
+                        report(format!("{}/{}/{}", path, seat, piece_index), StartingPositionsError::PieceIndexNotFound(piece_index));
+                        continue;
+                    }
                     Some(piece) => piece,
                 };
-                for &position in positions {
+                for (index, &position) in positions.iter().enumerate() {
+                    let path = format!("{}/{}/{}/{}", path, seat, piece_index, index);
                     // Check already used positions and add to list
                     if !found.insert(position) {
-                        return Err(StartingPositionsError::DuplicatePosition {
+                        report(path, StartingPositionsError::DuplicatePosition {
                             piece: piece.clone(),
                             position,
                         });
+                        continue;
                     }
 
                     match ruleset.board_type.get_space(position) {
                         Space::Normal => {}
-                        space => {
-                            return Err(StartingPositionsError::InvalidPositionForBoard {
-                                space,
-                                piece: piece.clone(),
-                                position,
-                            });
-                        }
+                        space => report(path, StartingPositionsError::InvalidPositionForBoard {
+                            space,
+                            piece: piece.clone(),
+                            position,
+                        }),
                     }
                 }
             }
         }
-        Ok(())
     }
-    fn verify_placement(_first_seat: u64, alternation_type: AlternationType, placement_area: &PlacementArea, piece_limits: &HashSet<PieceLimit>, ruleset: &Ruleset) -> Result<(), StartingPositionsError> {
-        alternation_type.verify(piece_limits)?;
-        placement_area.verify(ruleset)?;
-        PieceLimit::verify(piece_limits, ruleset)?;
-        Ok(())
+    fn verify_placement(alternation_type: AlternationType, placement_area: &PlacementArea, piece_limits: &HashSet<PieceLimit>, path: &str, ruleset: &Ruleset, report: &mut dyn FnMut(String, StartingPositionsError)) {
+        if let Err(error) = alternation_type.verify(piece_limits) {
+            report(format!("{}/alternation_type", path), error.into());
+        }
+        if let Err(error) = placement_area.verify(ruleset) {
+            report(format!("{}/placement_area", path), error.into());
+        }
+        if let Err(error) = PieceLimit::verify(piece_limits, ruleset) {
+            report(format!("{}/piece_limits", path), error.into());
+        }
+    }
+
+    /// The fixed positions of every piece, indexed by seat, piece, generic list of positions.
+    /// Mirrored positions are seat 0's, seat 1 gets the flipped or rotated copy.
+    /// `None` for `Placement`.
+    pub fn seat_positions(&self, ruleset: &Ruleset) -> Option<Vec<Vec<Vec<Coordinate>>>> {
+        let (piece_positions, mirror): (_, fn(&BoardType, Coordinate) -> Coordinate) = match self {
+            StartingPositions::MirroredFlipped(positions) => (positions, flip_coordinate),
+            StartingPositions::MirroredRotated(positions) => (positions, rotate_coordinate),
+            StartingPositions::NotMirrored(positions) => return Some(positions.clone()),
+            StartingPositions::Placement { .. } => return None,
+        };
+        let mirrored = piece_positions.iter()
+            .map(|positions| positions.iter().map(|&position| mirror(&ruleset.board_type, position)).collect())
+            .collect();
+        let mut out = vec![Vec::new(); ruleset.seats as usize];
+        if let Some(seat_0) = out.get_mut(0) {
+            *seat_0 = piece_positions.clone();
+        }
+        if let Some(seat_1) = out.get_mut(1) {
+            *seat_1 = mirrored;
+        }
+        Some(out)
     }
 
     pub fn verify(&self, ruleset: &Ruleset) -> StartingPositionsResult<()> {
+        let mut first = None;
+        self.verify_each(ruleset, &mut |_, error| {
+            first.get_or_insert(error);
+        });
+        first.map_or(Ok(()), Err)
+    }
+    /// Calls `report` for every problem found with its JSON pointer from the starting positions, like
+    /// `/NotMirrored/1/0/2`.
+    pub fn verify_each(&self, ruleset: &Ruleset, report: &mut dyn FnMut(String, StartingPositionsError)) {
         match self {
-            StartingPositions::MirroredFlipped(self_data) => {
-                Self::verify_mirrored_flipped(self_data, ruleset)
+            StartingPositions::MirroredFlipped(positions) => {
+                Self::verify_mirrored(positions, flip_coordinate, "/MirroredFlipped", ruleset, report)
             }
-            StartingPositions::MirroredRotated(self_data) => {
-                Self::verify_mirrored_rotated(self_data, ruleset)
+            StartingPositions::MirroredRotated(positions) => {
+                Self::verify_mirrored(positions, rotate_coordinate, "/MirroredRotated", ruleset, report)
             }
             StartingPositions::NotMirrored(positions) => {
-                Self::verify_not_mirrored(positions, ruleset)
+                Self::verify_not_mirrored(positions, "/NotMirrored", ruleset, report)
             }
             StartingPositions::Placement {
-                first_seat,
+                first_seat: _,
                 alternation_type,
                 placement_area,
                 piece_limits,
             } => Self::verify_placement(
-                *first_seat,
                 *alternation_type,
                 placement_area,
                 piece_limits,
+                "/Placement",
                 ruleset,
+                report,
             ),
         }
     }
@@ -226,6 +222,20 @@ pub enum StartingPositionsError {
     PlacementAreaError(PlacementAreaError),
     PieceLimitError(PieceLimitError),
 }
+impl StartingPositionsError {
+    /// Name of the error variant, or the code of the error it wraps.
+    pub fn code(&self) -> &'static str {
+        match self {
+            StartingPositionsError::SeatNumberDoesNotMatch(_) => "SeatNumberDoesNotMatch",
+            StartingPositionsError::PieceIndexNotFound(_) => "PieceIndexNotFound",
+            StartingPositionsError::DuplicatePosition { .. } => "DuplicatePosition",
+            StartingPositionsError::InvalidPositionForBoard { .. } => "InvalidPositionForBoard",
+            StartingPositionsError::AlterationTypeError(error) => error.code(),
+            StartingPositionsError::PlacementAreaError(error) => error.code(),
+            StartingPositionsError::PieceLimitError(error) => error.code(),
+        }
+    }
+}
 impl Display for StartingPositionsError {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         <Self as Debug>::fmt(self, f)
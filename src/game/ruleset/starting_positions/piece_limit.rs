@@ -8,6 +8,7 @@ use core::result::Result::{Err, Ok};
 use std::collections::HashSet;
 use std::error::Error;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::game::ruleset::piece_definition::PieceDefinition;
@@ -16,23 +17,28 @@ use crate::game::ruleset::Ruleset;
 /// Limits for piece placement.
 ///
 /// Hash, Eq, PartialEq are defined for the discriminant.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub enum PieceLimit {
     /// Limit to the total count of pieces.
     TotalLimit {
+        /// Must be > 0
+        #[schemars(range(min = 1))]
         limit: usize,
     },
     /// Limit to each type of piece.
     TypeCountLimit {
         /// Maps from pieces index to limit
+        /// Must be set for all pieces, each must be > 0.
         limits: Vec<usize>,
     },
     /// Limit by point count and total points available.
     PointLimit {
-        /// Must be set for all pieces.
+        /// Must be set for all pieces, each must be > 0.
         /// Maps from pieces index to points value
         point_values: Vec<usize>,
         /// The total limit for each side.
+        /// Must be > 0.
+        #[schemars(range(min = 1))]
         point_limit: usize,
     },
 }
@@ -97,6 +103,19 @@ pub enum PieceLimitError {
     PointLimitIs0,
     NoPieceFitsInPointLimit,
 }
+impl PieceLimitError {
+    /// Name of the error variant.
+    pub fn code(&self) -> &'static str {
+        match self {
+            PieceLimitError::LimitIs0 => "LimitIs0",
+            PieceLimitError::PieceIndexMismatch(_) => "PieceIndexMismatch",
+            PieceLimitError::LimitIs0ForPiece(_) => "LimitIs0ForPiece",
+            PieceLimitError::PointsIs0ForPiece => "PointsIs0ForPiece",
+            PieceLimitError::PointLimitIs0 => "PointLimitIs0",
+            PieceLimitError::NoPieceFitsInPointLimit => "NoPieceFitsInPointLimit",
+        }
+    }
+}
 impl Display for PieceLimitError {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         <Self as Debug>::fmt(self, f)
@@ -3,16 +3,21 @@ use std::error::Error;
 use std::fmt::{Debug, Display, Formatter};
 use std::fmt;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::game::coordinate::{Coordinate, flip_coordinate, rotate_coordinate};
+use crate::game::ruleset::board_type::BoardType;
 use crate::game::ruleset::board_type::space::Space;
 use crate::game::ruleset::Ruleset;
 
 /// Placement area definition.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub enum PlacementArea {
     /// Players can place on half the board.
+    /// `Rectangular` boards split between rows, seat 0 gets the top half and a middle row is left out.
+    /// `Custom` boards give each normal space to the seat whose goals are furthest away, ties are left out.
+    /// Every seat must have a goal on `Custom` boards.
     Half,
     /// Players can place on a mirrored set of places.
     /// Mirroring will flip.
@@ -29,7 +34,12 @@ pub enum PlacementArea {
 impl PlacementArea {
     pub fn verify(&self, ruleset: &Ruleset) -> PlacementAreaResult<()> {
         match self {
-            Self::Half => {},
+            Self::Half => if let BoardType::Custom(_) = ruleset.board_type {
+                let goals = goal_spaces(ruleset);
+                if let Some(seat) = (0..ruleset.seats).find(|&seat| goals[seat as usize].is_empty()) {
+                    return Err(PlacementAreaError::NoGoalForSeat(seat));
+                }
+            },
             Self::MirroredFlipped(positions) | Self::MirroredRotated(positions) => {
                 let func = if let Self::MirroredFlipped(_) = self { flip_coordinate } else { rotate_coordinate };
                 let mut found = positions.clone();
@@ -38,7 +48,7 @@ impl PlacementArea {
                         return Err(PlacementAreaError::PositionCannotPlace(Space::Invalid, position))
                     }
                     let opposite = func(&ruleset.board_type, position);
-                    if !found.insert(position) || found.insert(opposite) {
+                    if !found.insert(opposite) {
                         return Err(PlacementAreaError::PositionCollision(position));
                     }
                 }
@@ -59,13 +69,87 @@ impl PlacementArea {
         }
         Ok(())
     }
+
+    /// The coordinates `seat` may place on, seat 0 uses the unmirrored set.
+    pub fn seat_area(&self, ruleset: &Ruleset, seat: u64) -> HashSet<Coordinate> {
+        match self {
+            Self::Half => half_area(ruleset, seat),
+            Self::MirroredFlipped(positions) | Self::MirroredRotated(positions) => {
+                if seat == 0 {
+                    return positions.clone();
+                }
+                let func = if let Self::MirroredFlipped(_) = self { flip_coordinate } else { rotate_coordinate };
+                positions.iter().map(|&position| func(&ruleset.board_type, position)).collect()
+            }
+            Self::NonMirrored(seat_map) => seat_map.get(seat as usize).cloned().unwrap_or_default(),
+        }
+    }
+}
+/// The half of the board belonging to `seat`, see `PlacementArea::Half`.
+fn half_area(ruleset: &Ruleset, seat: u64) -> HashSet<Coordinate> {
+    match &ruleset.board_type {
+        BoardType::Rectangular { rows, .. } => {
+            let half = (*rows / 2) as i16;
+            let seat_rows = match seat {
+                0 => 1..=half,
+                1 => *rows as i16 - half + 1..=*rows as i16,
+                _ => return HashSet::new(),
+            };
+            seat_rows
+                .flat_map(|row| (0..ruleset.board_type.columns() as i16).map(move |column| Coordinate::new(row, column)))
+                .collect()
+        }
+        BoardType::Custom(board) => {
+            let goals = goal_spaces(ruleset);
+            board.indexed_iter()
+                .filter(|(_, space)| **space == Space::Normal)
+                .map(|((row, column), _)| Coordinate::new(row as i16, column as i16))
+                .filter(|&coordinate| {
+                    let distance = |seat: u64| goals[seat as usize].iter()
+                        .map(|goal| (goal.row - coordinate.row).abs().max((goal.column - coordinate.column).abs()))
+                        .min();
+                    let own = distance(seat);
+                    own.is_some() && (0..ruleset.seats).filter(|&other| other != seat).all(|other| distance(other) < own)
+                })
+                .collect()
+        }
+    }
+}
+
+/// Goal spaces of every seat, indexed by seat.
+fn goal_spaces(ruleset: &Ruleset) -> Vec<Vec<Coordinate>> {
+    let mut goals = vec![Vec::new(); ruleset.seats as usize];
+    for row in 0..ruleset.board_type.rows() as i16 {
+        for column in 0..ruleset.board_type.columns() as i16 {
+            let coordinate = Coordinate::new(row, column);
+            if let Space::Goal(seat) = ruleset.board_type.get_space(coordinate) {
+                if let Some(seat_goals) = goals.get_mut(seat as usize) {
+                    seat_goals.push(coordinate);
+                }
+            }
+        }
+    }
+    goals
 }
+
 pub type PlacementAreaResult<T> = Result<T, PlacementAreaError>;
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 pub enum PlacementAreaError {
     PositionCannotPlace(Space, Coordinate),
     PositionCollision(Coordinate),
     InvalidSeatNumber(usize),
+    NoGoalForSeat(u64),
+}
+impl PlacementAreaError {
+    /// Name of the error variant.
+    pub fn code(&self) -> &'static str {
+        match self {
+            PlacementAreaError::PositionCannotPlace(..) => "PositionCannotPlace",
+            PlacementAreaError::PositionCollision(_) => "PositionCollision",
+            PlacementAreaError::InvalidSeatNumber(_) => "InvalidSeatNumber",
+            PlacementAreaError::NoGoalForSeat(_) => "NoGoalForSeat",
+        }
+    }
 }
 impl Display for PlacementAreaError {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
@@ -73,3 +157,47 @@ impl Display for PlacementAreaError {
     }
 }
 impl Error for PlacementAreaError {}
+
+#[cfg(test)]
+mod test {
+    use ndarray::Array2;
+
+    use crate::game::Coordinate;
+    use crate::game::ruleset::board_type::BoardType;
+    use crate::game::ruleset::board_type::space::Space;
+    use crate::game::ruleset::standard::standard_rules;
+    use crate::game::ruleset::starting_positions::placement_area::{PlacementArea, PlacementAreaError};
+
+    #[test]
+    fn half_rectangular() {
+        let mut ruleset = standard_rules();
+        let top = PlacementArea::Half.seat_area(&ruleset, 0);
+        let bottom = PlacementArea::Half.seat_area(&ruleset, 1);
+        assert_eq!(top.len(), 50);
+        assert_eq!(bottom.len(), 50);
+        assert!(top.iter().all(|coordinate| (1..=5).contains(&coordinate.row)));
+        assert!(bottom.iter().all(|coordinate| (6..=10).contains(&coordinate.row)));
+
+        ruleset.board_type = BoardType::Rectangular { rows: 3, columns: 2, goal_locations: vec![] };
+        assert_eq!(PlacementArea::Half.seat_area(&ruleset, 0), vec![Coordinate::new(1, 0), Coordinate::new(1, 1)].into_iter().collect());
+        assert_eq!(PlacementArea::Half.seat_area(&ruleset, 1), vec![Coordinate::new(3, 0), Coordinate::new(3, 1)].into_iter().collect());
+    }
+
+    #[test]
+    fn half_custom() {
+        let mut ruleset = standard_rules();
+        let mut board = Array2::from_elem((4, 3), Space::Normal);
+        board[(0, 1)] = Space::Goal(1);
+        ruleset.board_type = BoardType::Custom(board.clone());
+        assert_eq!(PlacementArea::Half.verify(&ruleset), Err(PlacementAreaError::NoGoalForSeat(0)));
+
+        board[(3, 1)] = Space::Goal(0);
+        ruleset.board_type = BoardType::Custom(board);
+        PlacementArea::Half.verify(&ruleset).unwrap();
+        let top = PlacementArea::Half.seat_area(&ruleset, 0);
+        assert_eq!(top.len(), 5);
+        assert!(top.iter().all(|coordinate| coordinate.row < 2));
+        assert!(!top.contains(&Coordinate::new(0, 1)));
+        assert!(PlacementArea::Half.seat_area(&ruleset, 1).iter().all(|coordinate| coordinate.row >= 2));
+    }
+}
@@ -6,6 +6,7 @@ use std::error::Error;
 use std::fmt::{Debug, Display, Formatter};
 use std::fmt;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::game::ruleset::Ruleset;
@@ -13,22 +14,35 @@ use crate::game::ruleset::Ruleset;
 /// How the game is won.
 ///
 /// Hash, Eq, and PartialEq are based on the discriminate.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub enum VictoryCondition {
     /// Victory can be achieved by having a certain number of goals owned by pieces.
     /// Condition becomes impossible if player has less than amount pieces left.
     GoalCount {
         /// Goals that need to be occupied to achieve this condition.
+        /// Must be > 0.
+        #[schemars(range(min = 1))]
         amount: usize,
         /// Piece indexes that count for occupying goals.
+        /// Must not be empty.
+        #[schemars(length(min = 1))]
         valid_pieces: HashSet<usize>,
     },
     /// Victory can be achieved by capturing all of your opponents pieces.
     AllCaptured,
     /// Victory can be achieved by having a non-captured point difference.
-    PointDifference(usize),
+    /// Must be > 0.
+    PointDifference(#[schemars(range(min = 1))] usize),
 }
 impl VictoryCondition {
+    /// Name of the variant, a ruleset has at most one condition of each.
+    pub fn name(&self) -> &'static str {
+        match self {
+            VictoryCondition::GoalCount { .. } => "GoalCount",
+            VictoryCondition::AllCaptured => "AllCaptured",
+            VictoryCondition::PointDifference(_) => "PointDifference",
+        }
+    }
     pub fn verify(&self, ruleset: &Ruleset) -> VictoryConditionResult<()> {
         match self {
             VictoryCondition::GoalCount { amount, valid_pieces } => {
@@ -77,6 +91,18 @@ pub enum VictoryConditionError {
     PieceNotFound(usize),
     PointDifferenceIs0,
 }
+impl VictoryConditionError {
+    /// Name of the error variant.
+    pub fn code(&self) -> &'static str {
+        match self {
+            VictoryConditionError::AmountIs0 => "AmountIs0",
+            VictoryConditionError::NoValidPieces => "NoValidPieces",
+            VictoryConditionError::BoardHasNoGoal => "BoardHasNoGoal",
+            VictoryConditionError::PieceNotFound(_) => "PieceNotFound",
+            VictoryConditionError::PointDifferenceIs0 => "PointDifferenceIs0",
+        }
+    }
+}
 impl Display for VictoryConditionError {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         <Self as Debug>::fmt(self, f)
@@ -0,0 +1,422 @@
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Debug, Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::board::{Board, BoardError, BoardResult};
+use crate::game::capture::{apply_move, CapturedPiece};
+use crate::game::Coordinate;
+use crate::game::hidden_placement::{HiddenPiece, lock_hidden_placement};
+use crate::game::movement::{distinct_moves, legal_moves, Move};
+use crate::game::notation::{NotatedMove, RecordEntry};
+use crate::game::placement::{finish_placement, place_piece, PlacementError, PlacementState, placement_moves};
+use crate::game::ruleset::board_type::space::Space;
+use crate::game::ruleset::Ruleset;
+use crate::game::ruleset::starting_positions::alteration_type::AlternationType;
+use crate::game::ruleset::starting_positions::StartingPositions;
+use crate::game::zobrist::ZobristKeys;
+
+/// Something a seat does on its turn.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Action {
+    Move(Move),
+    Place {
+        coordinate: Coordinate,
+        piece: usize,
+    },
+    FinishPlacement,
+    HiddenPlacement {
+        seat: u64,
+        pieces: Vec<HiddenPiece>,
+    },
+}
+impl Action {
+    pub(crate) fn from_entry(entry: &RecordEntry) -> Self {
+        match entry {
+            RecordEntry::Move(notated) => Action::Move(notated.action.clone()),
+            RecordEntry::Place { coordinate, piece } => Action::Place { coordinate: *coordinate, piece: *piece },
+            RecordEntry::FinishPlacement => Action::FinishPlacement,
+            RecordEntry::HiddenPlacement { seat, pieces } => Action::HiddenPlacement { seat: *seat, pieces: pieces.clone() },
+        }
+    }
+}
+
+/// What changed in a ply, enough to take it back.
+#[derive(Clone, Debug)]
+enum Undo {
+    Move {
+        from: Coordinate,
+        to: Coordinate,
+        captured: Vec<CapturedPiece>,
+        current_seat: u64,
+        turn_number: usize,
+    },
+    Placement {
+        /// Spaces filled by the ply
+        placed: Vec<Coordinate>,
+        current_seat: u64,
+        placement: Option<PlacementState>,
+    },
+}
+
+#[derive(Clone, Debug)]
+struct Ply {
+    entry: RecordEntry,
+    undo: Undo,
+    /// Zobrist hash of the position after the ply
+    hash: u64,
+}
+
+/// A board along with every ply played on it.
+///
+/// Plies keep only what they changed so undoing never needs an earlier copy of the board.
+/// Undone plies are kept for redo until a different action is applied.
+/// The Zobrist hash of the position is updated with each move.
+#[derive(Clone, Debug)]
+pub struct GameState {
+    board: Board,
+    plies: Vec<Ply>,
+    /// Number of plies currently applied
+    current: usize,
+    keys: ZobristKeys,
+    /// Zobrist hash of the starting board
+    start_hash: u64,
+}
+impl GameState {
+    /// Starts from the initial board of the ruleset.
+    pub fn new(ruleset: &Ruleset) -> BoardResult<Self> {
+        Ok(Self::from_board(ruleset, Board::initial(ruleset)?))
+    }
+    pub fn from_board(ruleset: &Ruleset, board: Board) -> Self {
+        let keys = ZobristKeys::new(ruleset);
+        Self {
+            start_hash: keys.hash(&board),
+            board,
+            plies: Vec::new(),
+            current: 0,
+            keys,
+        }
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+    /// Number of plies currently applied.
+    pub fn ply(&self) -> usize {
+        self.current
+    }
+    /// Number of plies applied or waiting to be redone.
+    pub fn len(&self) -> usize {
+        self.plies.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.plies.is_empty()
+    }
+    /// Record entries of every applied ply.
+    pub fn history(&self) -> impl DoubleEndedIterator<Item=&RecordEntry> + '_ {
+        self.plies[..self.current].iter().map(|ply| &ply.entry)
+    }
+    /// Zobrist hash of the current position, see `ZobristKeys`.
+    pub fn hash(&self) -> u64 {
+        match self.current {
+            0 => self.start_hash,
+            current => self.plies[current - 1].hash,
+        }
+    }
+    pub fn keys(&self) -> &ZobristKeys {
+        &self.keys
+    }
+    /// Number of times the current position has occurred with the same seat to move, including now.
+    pub fn repetitions(&self) -> usize {
+        let current = self.hash();
+        let earlier = self.plies[..self.current].iter().filter(|ply| ply.hash == current).count();
+        earlier + (self.start_hash == current) as usize
+    }
+    /// Number of moves played since the last capture or the end of placement.
+    pub fn turns_since_capture(&self) -> usize {
+        self.history().rev()
+            .take_while(|entry| matches!(entry, RecordEntry::Move(notated) if notated.captures.is_empty()))
+            .count()
+    }
+    /// Every action the seat to move can take, placements during the placement phase and legal moves after.
+    ///
+    /// Hidden placements are submitted by each seat on its own and are not included.
+    pub fn legal_actions(&self, ruleset: &Ruleset) -> Vec<Action> {
+        if let StartingPositions::Placement { alternation_type: AlternationType::Hidden, .. } = ruleset.starting_positions {
+            if self.board.placement().is_some() {
+                return Vec::new();
+            }
+        }
+        if self.board.placement().is_some() {
+            placement_moves(ruleset, &self.board).into_iter()
+                .map(|(coordinate, piece)| Action::Place { coordinate, piece })
+                .chain(std::iter::once(Action::FinishPlacement))
+                .collect()
+        } else {
+            legal_moves(ruleset, &self.board, self.board.current_seat()).into_iter().map(Action::Move).collect()
+        }
+    }
+    /// `legal_actions` with one jump path for each landing spot and set of captures, for the AI, see `distinct_moves`.
+    pub fn distinct_actions(&self, ruleset: &Ruleset) -> Vec<Action> {
+        if self.board.placement().is_some() {
+            self.legal_actions(ruleset)
+        } else {
+            distinct_moves(ruleset, &self.board, self.board.current_seat()).into_iter().map(|(action, _)| Action::Move(action)).collect()
+        }
+    }
+
+    /// Applies `action`, dropping any undone plies.
+    ///
+    /// Only the shape of moves is checked, use the legal move list to check the move is allowed.
+    pub fn apply(&mut self, ruleset: &Ruleset, action: Action) -> StateResult<()> {
+        let ply = self.apply_ply(ruleset, action)?;
+        self.plies.truncate(self.current);
+        self.plies.push(ply);
+        self.current += 1;
+        Ok(())
+    }
+    /// Takes back the last applied ply, `false` if there is none.
+    pub fn undo(&mut self) -> bool {
+        if self.current == 0 {
+            return false;
+        }
+        self.current -= 1;
+        let ply = &self.plies[self.current];
+        match &ply.undo {
+            Undo::Move { from, to, captured, current_seat, turn_number } => {
+                let piece = self.board.remove(*to).expect("Moved piece is on the board");
+                for capture in captured.iter().rev() {
+                    self.board.uncapture(capture.position, capture.piece);
+                }
+                self.board.set(*from, Some(piece));
+                self.board.set_current_seat(*current_seat);
+                self.board.set_turn_number(*turn_number);
+            }
+            Undo::Placement { placed, current_seat, placement } => {
+                for &position in placed {
+                    self.board.remove(position).expect("Placed piece is on the board");
+                }
+                self.board.set_current_seat(*current_seat);
+                self.board.set_placement(placement.clone());
+            }
+        }
+        true
+    }
+    /// Applies the next undone ply again, `false` if there is none.
+    pub fn redo(&mut self, ruleset: &Ruleset) -> StateResult<bool> {
+        if self.current == self.plies.len() {
+            return Ok(false);
+        }
+        let action = Action::from_entry(&self.plies[self.current].entry);
+        self.plies[self.current] = self.apply_ply(ruleset, action)?;
+        self.current += 1;
+        Ok(true)
+    }
+    /// Undoes or redoes until `ply` plies are applied.
+    pub fn go_to(&mut self, ruleset: &Ruleset, ply: usize) -> StateResult<()> {
+        if ply > self.plies.len() {
+            return Err(StateError::PlyNotFound(ply));
+        }
+        while self.current > ply {
+            self.undo();
+        }
+        while self.current < ply {
+            self.redo(ruleset)?;
+        }
+        Ok(())
+    }
+
+    fn apply_ply(&mut self, ruleset: &Ruleset, action: Action) -> StateResult<Ply> {
+        let current_seat = self.board.current_seat();
+        let turn_number = self.board.turn_number();
+        let placement = self.board.placement().cloned();
+        let before: HashSet<_> = match &action {
+            Action::Move(_) => HashSet::new(),
+            _ => self.board.pieces().map(|(position, _)| position).collect(),
+        };
+        let entry = match action {
+            Action::Move(action) => {
+                let (from, to) = match (action.from(), action.to()) {
+                    (Some(from), Some(to)) => (from, to),
+                    _ => return Err(BoardError::JumpPathTooShort(0).into()),
+                };
+                let piece = self.board.get(from).ok_or(BoardError::NoPieceAt(from))?;
+                let captured = apply_move(ruleset, &mut self.board, &action)?;
+                let hash = captured.iter().fold(self.hash(), |hash, capture| hash ^ self.keys.piece(capture.position, capture.piece))
+                    ^ self.keys.piece(from, piece) ^ self.keys.piece(to, piece)
+                    ^ self.keys.seat_to_move(current_seat) ^ self.keys.seat_to_move(self.board.current_seat());
+                let notated = NotatedMove {
+                    piece: piece.piece,
+                    captures: captured.iter().map(|capture| capture.position).collect(),
+                    action,
+                    goal: matches!(ruleset.board_type.get_space(to), Space::Goal(_)),
+                };
+                return Ok(Ply {
+                    entry: RecordEntry::Move(notated),
+                    undo: Undo::Move { from, to, captured, current_seat, turn_number },
+                    hash,
+                });
+            }
+            Action::Place { coordinate, piece } => {
+                place_piece(ruleset, &mut self.board, coordinate, piece)?;
+                RecordEntry::Place { coordinate, piece }
+            }
+            Action::FinishPlacement => {
+                finish_placement(ruleset, &mut self.board)?;
+                RecordEntry::FinishPlacement
+            }
+            Action::HiddenPlacement { seat, pieces } => {
+                lock_hidden_placement(ruleset, &mut self.board, seat, pieces.clone(), None)?;
+                RecordEntry::HiddenPlacement { seat, pieces }
+            }
+        };
+        let placed = self.board.pieces()
+            .map(|(position, _)| position)
+            .filter(|position| !before.contains(position))
+            .collect();
+        Ok(Ply {
+            entry,
+            undo: Undo::Placement { placed, current_seat, placement },
+            hash: self.keys.hash(&self.board),
+        })
+    }
+}
+
+pub type StateResult<T> = Result<T, StateError>;
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum StateError {
+    PlyNotFound(usize),
+    BoardError(BoardError),
+    PlacementError(PlacementError),
+}
+impl Display for StateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        <Self as Debug>::fmt(self, f)
+    }
+}
+impl Error for StateError {
+    fn cause(&self) -> Option<&dyn Error> {
+        match self {
+            Self::PlyNotFound(_) => None,
+            Self::BoardError(error) => Some(error),
+            Self::PlacementError(error) => Some(error),
+        }
+    }
+}
+impl From<BoardError> for StateError {
+    fn from(from: BoardError) -> Self {
+        Self::BoardError(from)
+    }
+}
+impl From<PlacementError> for StateError {
+    fn from(from: PlacementError) -> Self {
+        Self::PlacementError(from)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use crate::game::board::{Board, BoardError, BoardPiece};
+    use crate::game::Coordinate;
+    use crate::game::movement::{legal_moves, Move};
+    use crate::game::notation::RecordEntry;
+    use crate::game::ruleset::piece_definition::CaptureTimingRule;
+    use crate::game::ruleset::standard::standard_rules;
+    use crate::game::ruleset::starting_positions::alteration_type::AlternationType;
+    use crate::game::ruleset::starting_positions::placement_area::PlacementArea;
+    use crate::game::ruleset::starting_positions::StartingPositions;
+    use crate::game::state::{Action, GameState, StateError};
+
+    #[test]
+    fn empty_jump_path() {
+        let ruleset = standard_rules();
+        let mut state = GameState::new(&ruleset).unwrap();
+        let board = state.board().clone();
+        let result = state.apply(&ruleset, Action::Move(Move::Jump { path: Vec::new() }));
+        assert!(matches!(result, Err(StateError::BoardError(BoardError::JumpPathTooShort(0)))));
+        assert_eq!(state.board(), &board);
+        assert_eq!(state.ply(), 0);
+    }
+
+    #[test]
+    fn undo_redo_moves() {
+        let ruleset = standard_rules();
+        let mut state = GameState::new(&ruleset).unwrap();
+        let mut boards = vec![state.board().clone()];
+        for _ in 0..12 {
+            let action = legal_moves(&ruleset, state.board(), state.board().current_seat()).pop().unwrap();
+            state.apply(&ruleset, Action::Move(action)).unwrap();
+            boards.push(state.board().clone());
+        }
+        assert_eq!(state.hash(), state.keys().hash(state.board()));
+        for ply in (0..12).rev() {
+            assert!(state.undo());
+            assert_eq!(state.board(), &boards[ply]);
+        }
+        assert!(!state.undo());
+        state.go_to(&ruleset, 12).unwrap();
+        assert_eq!(state.board(), &boards[12]);
+        state.go_to(&ruleset, 5).unwrap();
+        assert_eq!(state.board(), &boards[5]);
+        assert_eq!(state.history().count(), 5);
+        assert_eq!(state.go_to(&ruleset, 13), Err(StateError::PlyNotFound(13)));
+
+        // A new action drops the undone plies
+        let action = legal_moves(&ruleset, state.board(), state.board().current_seat()).remove(0);
+        state.apply(&ruleset, Action::Move(action)).unwrap();
+        assert_eq!(state.len(), 6);
+        assert!(!state.redo(&ruleset).unwrap());
+    }
+
+    #[test]
+    fn undo_after_turn_captures() {
+        let ruleset = standard_rules();
+        assert_eq!(ruleset.pieces[0].capture_timing_rule, CaptureTimingRule::AfterTurn);
+        let mut board = Board::new(&ruleset);
+        board.place(&ruleset, Coordinate::new(5, 2), BoardPiece::new(0, 0)).unwrap();
+        board.place(&ruleset, Coordinate::new(6, 3), BoardPiece::new(1, 0)).unwrap();
+        board.place(&ruleset, Coordinate::new(6, 5), BoardPiece::new(1, 1)).unwrap();
+        let before = board.clone();
+        let mut state = GameState::from_board(&ruleset, board);
+        let action = Action::Move(Move::Jump { path: vec![Coordinate::new(5, 2), Coordinate::new(7, 4), Coordinate::new(5, 6)] });
+        assert!(state.legal_actions(&ruleset).contains(&action));
+        state.apply(&ruleset, action).unwrap();
+        match state.history().next() {
+            Some(RecordEntry::Move(notated)) => assert_eq!(notated.captures, vec![Coordinate::new(6, 3), Coordinate::new(6, 5)]),
+            entry => panic!("Expected a move, found {:?}", entry),
+        }
+        assert_eq!(state.board().pieces().count(), 1);
+        assert_eq!(state.board().captured(1), &[0, 1]);
+        assert_eq!(state.hash(), state.keys().hash(state.board()));
+        state.undo();
+        assert_eq!(state.board(), &before);
+        state.redo(&ruleset).unwrap();
+        assert_eq!(state.board().pieces().count(), 1);
+    }
+
+    #[test]
+    fn undo_placement() {
+        let mut ruleset = standard_rules();
+        let area: HashSet<_> = (0..2).map(|column| Coordinate::new(1, column)).collect();
+        ruleset.starting_positions = StartingPositions::Placement {
+            first_seat: 0,
+            alternation_type: AlternationType::TurnsCount { per_turn_count: 1 },
+            placement_area: PlacementArea::MirroredFlipped(area),
+            piece_limits: HashSet::new(),
+        };
+        let mut state = GameState::new(&ruleset).unwrap();
+        let start = state.board().clone();
+        for &(row, column) in &[(1, 0), (10, 0), (1, 1), (10, 1)] {
+            state.apply(&ruleset, Action::Place { coordinate: Coordinate::new(row, column), piece: 0 }).unwrap();
+        }
+        assert!(state.board().placement().is_none());
+        state.go_to(&ruleset, 0).unwrap();
+        assert_eq!(state.board(), &start);
+        state.go_to(&ruleset, 4).unwrap();
+        assert_eq!(state.board().pieces().count(), 4);
+    }
+}
@@ -0,0 +1,320 @@
+use serde::{Deserialize, Serialize};
+
+use crate::game::board::Board;
+use crate::game::movement::has_legal_move;
+use crate::game::ruleset::board_type::space::Space;
+use crate::game::ruleset::draw_rule::{DrawRule, StalemateOutcome};
+use crate::game::ruleset::Ruleset;
+use crate::game::state::GameState;
+use crate::game::ruleset::victory_condition::VictoryCondition;
+
+/// Why a seat won or lost.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum EndReason {
+    /// The condition that was met.
+    Victory(VictoryCondition),
+    /// Every victory condition became impossible for the losing team, the winners are the last team left.
+    NoConditionPossible,
+    /// The seat to move had no legal move under `DrawRule::NoLegalMove`.
+    NoLegalMove,
+}
+
+/// The result of a game for a single seat.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum SeatOutcome {
+    /// The seat has not won or lost yet.
+    Playing,
+    /// The seat's team won.
+    Won(EndReason),
+    /// The seat lost.
+    Lost(EndReason),
+    /// The game was drawn by the rule.
+    Drawn(DrawRule),
+}
+impl SeatOutcome {
+    pub fn is_playing(&self) -> bool {
+        matches!(self, Self::Playing)
+    }
+}
+
+/// Whether a seat has won or the game was drawn.
+///
+/// With more than two teams the others play on after a team loses.
+pub fn is_game_over(outcomes: &[SeatOutcome]) -> bool {
+    outcomes.iter().any(|outcome| matches!(outcome, SeatOutcome::Won(_) | SeatOutcome::Drawn(_)))
+}
+
+/// Decides the outcome for every seat using the victory conditions then the draw rules, indexed by seat.
+///
+/// A seat with no legal move outside of placement ends the game as its `DrawRule::NoLegalMove` says, or draws
+/// without one. Repetition and turns without capture are then checked against the state's history.
+pub fn evaluate_game(ruleset: &Ruleset, state: &GameState) -> Vec<SeatOutcome> {
+    let board = state.board();
+    let outcomes = evaluate_victory(ruleset, board);
+    if outcomes.iter().any(|outcome| !outcome.is_playing()) {
+        return outcomes;
+    }
+    let drawn = |rule: DrawRule| vec![SeatOutcome::Drawn(rule); ruleset.seats as usize];
+
+    if board.placement().is_none() && !has_legal_move(ruleset, board, board.current_seat()) {
+        let rule = ruleset.draw_rules.get(&DrawRule::NoLegalMove(StalemateOutcome::Draw)).copied();
+        let stuck_wins = match rule {
+            Some(DrawRule::NoLegalMove(StalemateOutcome::Loss)) => false,
+            Some(DrawRule::NoLegalMove(StalemateOutcome::Win)) => true,
+            _ => return drawn(rule.unwrap_or(DrawRule::NoLegalMove(StalemateOutcome::Draw))),
+        };
+        return (0..ruleset.seats)
+            .map(|seat| if ruleset.are_allied(seat, board.current_seat()) == stuck_wins {
+                SeatOutcome::Won(EndReason::NoLegalMove)
+            } else {
+                SeatOutcome::Lost(EndReason::NoLegalMove)
+            })
+            .collect();
+    }
+    for &rule in &ruleset.draw_rules {
+        let triggered = match rule {
+            DrawRule::Repetition(count) => state.repetitions() >= count,
+            DrawRule::NoCapture(turns) => state.turns_since_capture() >= turns,
+            DrawRule::NoLegalMove(_) => false,
+        };
+        if triggered {
+            return drawn(rule);
+        }
+    }
+    outcomes
+}
+
+/// Decides the outcome for every seat, indexed by seat.
+///
+/// Allied seats share pieces, goals, and results.
+/// Conditions are checked in the order `GoalCount`, `AllCaptured`, `PointDifference`, every team meeting the first
+/// condition that is met wins.
+/// A team with no possible condition left has lost, if only one team is left it wins.
+pub fn evaluate_victory(ruleset: &Ruleset, board: &Board) -> Vec<SeatOutcome> {
+    let mut conditions: Vec<_> = ruleset.victory_conditions.iter().collect();
+    conditions.sort_by_key(|condition| condition_order(condition));
+    let teams = ruleset.teams();
+    let mut outcomes = vec![SeatOutcome::Playing; ruleset.seats as usize];
+
+    for &condition in &conditions {
+        let winners: Vec<_> = teams.iter()
+            .filter(|team| condition_met(ruleset, board, condition, team))
+            .collect();
+        if !winners.is_empty() {
+            for team in &teams {
+                let outcome = if winners.contains(&team) {
+                    SeatOutcome::Won(EndReason::Victory(condition.clone()))
+                } else {
+                    SeatOutcome::Lost(EndReason::Victory(condition.clone()))
+                };
+                set_team(&mut outcomes, team, outcome);
+            }
+            return outcomes;
+        }
+    }
+
+    let mut remaining = Vec::new();
+    for team in &teams {
+        if !conditions.is_empty() && conditions.iter().all(|condition| !condition_possible(board, condition, team)) {
+            set_team(&mut outcomes, team, SeatOutcome::Lost(EndReason::NoConditionPossible));
+        } else {
+            remaining.push(team);
+        }
+    }
+    if remaining.len() == 1 && teams.len() > 1 {
+        set_team(&mut outcomes, remaining[0], SeatOutcome::Won(EndReason::NoConditionPossible));
+    }
+    outcomes
+}
+
+/// Number of goals for `team` occupied by its pieces that count for the goal.
+pub fn goals_held(ruleset: &Ruleset, board: &Board, team: &[u64], valid_pieces: &[usize]) -> usize {
+    board.pieces()
+        .filter(|(position, piece)| {
+            team.contains(&piece.seat)
+                && valid_pieces.contains(&piece.piece)
+                && matches!(ruleset.board_type.get_space(*position), Space::Goal(seat) if team.contains(&seat))
+        })
+        .count()
+}
+/// Total value of the pieces `team` has left on the board.
+pub fn team_points(ruleset: &Ruleset, board: &Board, team: &[u64]) -> usize {
+    board.pieces()
+        .filter(|(_, piece)| team.contains(&piece.seat))
+        .map(|(_, piece)| ruleset.piece_value(piece.piece))
+        .sum()
+}
+
+fn set_team(outcomes: &mut [SeatOutcome], team: &[u64], outcome: SeatOutcome) {
+    for &seat in team {
+        outcomes[seat as usize] = outcome.clone();
+    }
+}
+
+fn condition_order(condition: &VictoryCondition) -> usize {
+    match condition {
+        VictoryCondition::GoalCount { .. } => 0,
+        VictoryCondition::AllCaptured => 1,
+        VictoryCondition::PointDifference(_) => 2,
+    }
+}
+
+fn condition_met(ruleset: &Ruleset, board: &Board, condition: &VictoryCondition, team: &[u64]) -> bool {
+    match condition {
+        VictoryCondition::GoalCount { amount, valid_pieces } => {
+            let valid_pieces: Vec<_> = valid_pieces.iter().copied().collect();
+            goals_held(ruleset, board, team, &valid_pieces) >= *amount
+        }
+        VictoryCondition::AllCaptured => board.pieces().all(|(_, piece)| team.contains(&piece.seat)),
+        VictoryCondition::PointDifference(difference) => {
+            let points = team_points(ruleset, board, team);
+            ruleset.teams().iter()
+                .filter(|other| other.as_slice() != team)
+                .all(|other| points >= team_points(ruleset, board, other) + difference)
+        }
+    }
+}
+
+fn condition_possible(board: &Board, condition: &VictoryCondition, team: &[u64]) -> bool {
+    match condition {
+        VictoryCondition::GoalCount { amount, valid_pieces } => {
+            let pieces_left = board.pieces()
+                .filter(|(_, piece)| team.contains(&piece.seat) && valid_pieces.contains(&piece.piece))
+                .count();
+            pieces_left >= *amount
+        }
+        VictoryCondition::AllCaptured | VictoryCondition::PointDifference(_) => board.pieces().any(|(_, piece)| team.contains(&piece.seat)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::game::board::{Board, BoardPiece};
+    use crate::game::Coordinate;
+    use crate::game::movement::Move;
+    use crate::game::ruleset::draw_rule::{DrawRule, StalemateOutcome};
+    use crate::game::ruleset::piece_definition::MoveRule;
+    use crate::game::ruleset::standard::standard_rules;
+    use crate::game::ruleset::victory_condition::VictoryCondition;
+    use crate::game::state::{Action, GameState};
+    use crate::game::victory::{EndReason, evaluate_game, evaluate_victory, SeatOutcome};
+
+    #[test]
+    fn goal_count_victory() {
+        let ruleset = standard_rules();
+        let mut board = Board::new(&ruleset);
+        for &(row, column, seat) in &[(11, 4, 0), (5, 5, 0), (2, 2, 1), (3, 3, 1)] {
+            board.place(&ruleset, Coordinate::new(row, column), BoardPiece::new(seat, 0)).unwrap();
+        }
+        assert_eq!(evaluate_victory(&ruleset, &board), vec![SeatOutcome::Playing, SeatOutcome::Playing]);
+
+        board.move_piece(&ruleset, Coordinate::new(5, 5), Coordinate::new(11, 5)).unwrap();
+        match evaluate_victory(&ruleset, &board).as_slice() {
+            [SeatOutcome::Won(won), SeatOutcome::Lost(lost)] => {
+                assert!(matches!(won, EndReason::Victory(VictoryCondition::GoalCount { amount: 2, .. })));
+                assert!(matches!(lost, EndReason::Victory(VictoryCondition::GoalCount { amount: 2, .. })));
+            }
+            outcomes => panic!("unexpected outcomes {:?}", outcomes),
+        }
+    }
+
+    #[test]
+    fn goal_count_impossible() {
+        let ruleset = standard_rules();
+        let mut board = Board::new(&ruleset);
+        for &(row, column, seat) in &[(5, 5, 0), (2, 2, 1), (3, 3, 1)] {
+            board.place(&ruleset, Coordinate::new(row, column), BoardPiece::new(seat, 0)).unwrap();
+        }
+        // Seat 0 can still capture everything
+        assert_eq!(evaluate_victory(&ruleset, &board), vec![SeatOutcome::Playing, SeatOutcome::Playing]);
+
+        let mut ruleset = ruleset;
+        ruleset.victory_conditions.remove(&VictoryCondition::AllCaptured);
+        assert_eq!(evaluate_victory(&ruleset, &board), vec![SeatOutcome::Lost(EndReason::NoConditionPossible), SeatOutcome::Won(EndReason::NoConditionPossible)]);
+    }
+
+    #[test]
+    fn all_captured_with_allies() {
+        let mut ruleset = standard_rules();
+        ruleset.seats = 3;
+        ruleset.allies = vec![vec![0, 2].into_iter().collect()];
+        let mut board = Board::new(&ruleset);
+        board.place(&ruleset, Coordinate::new(5, 5), BoardPiece::new(0, 1)).unwrap();
+        board.place(&ruleset, Coordinate::new(6, 6), BoardPiece::new(2, 1)).unwrap();
+        assert_eq!(evaluate_victory(&ruleset, &board), vec![
+            SeatOutcome::Won(EndReason::Victory(VictoryCondition::AllCaptured)),
+            SeatOutcome::Lost(EndReason::Victory(VictoryCondition::AllCaptured)),
+            SeatOutcome::Won(EndReason::Victory(VictoryCondition::AllCaptured)),
+        ]);
+    }
+
+    #[test]
+    fn point_difference() {
+        let mut ruleset = standard_rules();
+        ruleset.victory_conditions = vec![VictoryCondition::PointDifference(2)].into_iter().collect();
+        let mut board = Board::new(&ruleset);
+        for &(row, column, seat) in &[(5, 5, 0), (5, 6, 0), (2, 2, 1)] {
+            board.place(&ruleset, Coordinate::new(row, column), BoardPiece::new(seat, 0)).unwrap();
+        }
+        assert_eq!(evaluate_victory(&ruleset, &board), vec![SeatOutcome::Playing, SeatOutcome::Playing]);
+        board.place(&ruleset, Coordinate::new(5, 7), BoardPiece::new(0, 0)).unwrap();
+        assert!(matches!(evaluate_victory(&ruleset, &board).as_slice(), [
+            SeatOutcome::Won(EndReason::Victory(VictoryCondition::PointDifference(2))),
+            SeatOutcome::Lost(EndReason::Victory(VictoryCondition::PointDifference(2))),
+        ]));
+    }
+
+    fn step(row: i16, from: i16, to: i16) -> Action {
+        Action::Move(Move::Step { from: Coordinate::new(row, from), to: Coordinate::new(row, to) })
+    }
+
+    #[test]
+    fn repetition_and_no_capture() {
+        let mut ruleset = standard_rules();
+        ruleset.draw_rules = vec![DrawRule::Repetition(3), DrawRule::NoCapture(50)].into_iter().collect();
+        let mut board = Board::new(&ruleset);
+        board.place(&ruleset, Coordinate::new(8, 5), BoardPiece::new(0, 0)).unwrap();
+        board.place(&ruleset, Coordinate::new(2, 2), BoardPiece::new(1, 0)).unwrap();
+        let mut state = GameState::from_board(&ruleset, board);
+        for _ in 0..2 {
+            for action in [step(8, 5, 6), step(2, 2, 3), step(8, 6, 5), step(2, 3, 2)].iter().cloned() {
+                assert_eq!(evaluate_game(&ruleset, &state), vec![SeatOutcome::Playing, SeatOutcome::Playing]);
+                state.apply(&ruleset, action).unwrap();
+            }
+        }
+        assert_eq!(state.repetitions(), 3);
+        assert!(matches!(evaluate_game(&ruleset, &state).as_slice(), [
+            SeatOutcome::Drawn(DrawRule::Repetition(3)),
+            SeatOutcome::Drawn(DrawRule::Repetition(3)),
+        ]));
+
+        ruleset.draw_rules = vec![DrawRule::NoCapture(8)].into_iter().collect();
+        assert!(matches!(evaluate_game(&ruleset, &state).as_slice(), [
+            SeatOutcome::Drawn(DrawRule::NoCapture(8)),
+            SeatOutcome::Drawn(DrawRule::NoCapture(8)),
+        ]));
+        state.undo();
+        assert_eq!(evaluate_game(&ruleset, &state), vec![SeatOutcome::Playing, SeatOutcome::Playing]);
+    }
+
+    #[test]
+    fn no_legal_move() {
+        let mut ruleset = standard_rules();
+        ruleset.pieces[0].move_rule = MoveRule::None;
+        ruleset.draw_rules = vec![DrawRule::NoLegalMove(StalemateOutcome::Loss)].into_iter().collect();
+        let mut board = Board::new(&ruleset);
+        board.place(&ruleset, Coordinate::new(5, 5), BoardPiece::new(0, 0)).unwrap();
+        board.place(&ruleset, Coordinate::new(2, 2), BoardPiece::new(1, 1)).unwrap();
+        let state = GameState::from_board(&ruleset, board);
+        assert_eq!(evaluate_game(&ruleset, &state), vec![
+            SeatOutcome::Lost(EndReason::NoLegalMove),
+            SeatOutcome::Won(EndReason::NoLegalMove),
+        ]);
+
+        ruleset.draw_rules.clear();
+        assert!(matches!(evaluate_game(&ruleset, &state).as_slice(), [
+            SeatOutcome::Drawn(DrawRule::NoLegalMove(StalemateOutcome::Draw)),
+            SeatOutcome::Drawn(DrawRule::NoLegalMove(StalemateOutcome::Draw)),
+        ]));
+    }
+}
@@ -0,0 +1,122 @@
+use crate::game::board::{Board, BoardPiece};
+use crate::game::Coordinate;
+use crate::game::hidden_placement::HiddenSubmission;
+use crate::game::placement::PlacementState;
+use crate::game::ruleset::Ruleset;
+
+/// Seed for the key sequence, changing it changes every stored hash.
+const SEED: u64 = 0x4b61_7074_6f5a_6f62;
+
+/// Random keys for 64 bit Zobrist hashes of boards.
+///
+/// Keys only depend on the board size, seats, and pieces of the ruleset and are generated with SplitMix64 so hashes
+/// are the same across runs and can be stored.
+#[derive(Clone, Debug)]
+pub struct ZobristKeys {
+    /// Indexed by row, column, seat, then piece
+    pieces: Vec<u64>,
+    /// Indexed by the seat to move
+    seats: Vec<u64>,
+    columns: usize,
+    seat_count: usize,
+    piece_count: usize,
+}
+impl ZobristKeys {
+    pub fn new(ruleset: &Ruleset) -> Self {
+        let rows = ruleset.board_type.rows() as usize;
+        let columns = ruleset.board_type.columns() as usize;
+        let seat_count = ruleset.seats as usize;
+        let piece_count = ruleset.pieces.len();
+        let mut state = SEED;
+        let mut next = || {
+            state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            split_mix(state)
+        };
+        Self {
+            pieces: (0..rows * columns * seat_count * piece_count).map(|_| next()).collect(),
+            seats: (0..seat_count).map(|_| next()).collect(),
+            columns,
+            seat_count,
+            piece_count,
+        }
+    }
+
+    /// Key for `piece` standing on `coordinate`.
+    pub fn piece(&self, coordinate: Coordinate, piece: BoardPiece) -> u64 {
+        let space = coordinate.row as usize * self.columns + coordinate.column as usize;
+        self.pieces[(space * self.seat_count + piece.seat as usize) * self.piece_count + piece.piece]
+    }
+    /// Key for `seat` being the seat to move.
+    pub fn seat_to_move(&self, seat: u64) -> u64 {
+        self.seats[seat as usize]
+    }
+    /// Key for the placement progress, 0 once placement is over.
+    pub fn placement(&self, placement: Option<&PlacementState>) -> u64 {
+        let state = match placement {
+            None => return 0,
+            Some(state) => state,
+        };
+        let mut hash = SEED;
+        let mut add = |value: u64| hash = split_mix(hash ^ value);
+        add(state.placed_this_turn() as u64);
+        for seat in 0..self.seat_count as u64 {
+            state.placed(seat).iter().for_each(|&count| add(count as u64));
+            add(state.is_finished(seat) as u64);
+            match &state.hidden()[seat as usize] {
+                HiddenSubmission::Waiting => add(0),
+                HiddenSubmission::Committed(commitment) => {
+                    add(1);
+                    commitment.iter().for_each(|&byte| add(byte as u64));
+                }
+                HiddenSubmission::Locked(pieces) => {
+                    add(2);
+                    for piece in pieces {
+                        add(piece.coordinate.row as u64);
+                        add(piece.coordinate.column as u64);
+                        add(piece.piece as u64);
+                    }
+                }
+                HiddenSubmission::Withheld => add(3),
+            }
+        }
+        hash
+    }
+
+    /// Hashes a whole board, use the individual keys to update a hash as pieces change.
+    pub fn hash(&self, board: &Board) -> u64 {
+        board.pieces().fold(self.seat_to_move(board.current_seat()) ^ self.placement(board.placement()), |hash, (coordinate, piece)| {
+            hash ^ self.piece(coordinate, piece)
+        })
+    }
+}
+
+fn split_mix(value: u64) -> u64 {
+    let mut z = value;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::game::board::{Board, BoardPiece};
+    use crate::game::Coordinate;
+    use crate::game::ruleset::standard::standard_rules;
+    use crate::game::zobrist::ZobristKeys;
+
+    #[test]
+    fn stable_keys() {
+        let ruleset = standard_rules();
+        let keys = ZobristKeys::new(&ruleset);
+        let board = Board::initial(&ruleset).unwrap();
+        // Stored hashes depend on this value never changing
+        assert_eq!(keys.hash(&board), 3163550677191641967);
+        assert_eq!(ZobristKeys::new(&ruleset).hash(&board), keys.hash(&board));
+
+        let mut other = Board::new(&ruleset);
+        other.place(&ruleset, Coordinate::new(3, 3), BoardPiece::new(1, 0)).unwrap();
+        assert_eq!(keys.hash(&other), keys.seat_to_move(0) ^ keys.piece(Coordinate::new(3, 3), BoardPiece::new(1, 0)));
+        other.end_turn();
+        assert_ne!(keys.hash(&other), keys.seat_to_move(0) ^ keys.piece(Coordinate::new(3, 3), BoardPiece::new(1, 0)));
+    }
+}
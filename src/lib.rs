@@ -0,0 +1,8 @@
+pub mod api;
+pub mod database;
+pub mod engine;
+pub mod game;
+pub mod tournament;
+
+pub mod chat;
+pub mod util;
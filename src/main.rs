@@ -3,15 +3,8 @@ use std::sync::Arc;
 use serde::Deserialize;
 use warp::Filter;
 
-use crate::api::api_handler;
-use crate::database::connection_pool::ConnectionPool;
-
-pub mod api;
-pub mod database;
-pub mod game;
-
-pub mod chat;
-pub mod util;
+use kapto_web::api::{api_handler, ruleset_schema_handler};
+use kapto_web::database::connection_pool::ConnectionPool;
 
 #[tokio::main]
 async fn main() {
@@ -46,11 +39,15 @@ async fn main() {
         .and(json_body())
         .and(ConnectionPool::filter(pool.clone()))
         .and_then(api_handler);
+    let ruleset_schema_route = warp::path!("api" / "ruleset_schema")
+        .and(warp::get())
+        .map(ruleset_schema_handler);
 
     let web_route = warp::fs::dir("website/dist");
 
     warp::serve(
         api_route
+            .or(ruleset_schema_route)
             .or(web_route)
     ).run(([0, 0, 0, 0], 3030)).await;
 }
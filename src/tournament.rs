@@ -0,0 +1,340 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+
+use crate::game::ai::{alpha_beta, mcts, SearchBudget};
+use crate::game::ai::evaluation::EvaluationWeights;
+use crate::game::ai::mcts::MctsConfig;
+use crate::game::board::BoardResult;
+use crate::game::ruleset::draw_rule::DrawRule;
+use crate::game::ruleset::Ruleset;
+use crate::game::ruleset::victory_condition::VictoryCondition;
+use crate::game::state::GameState;
+use crate::game::victory::{evaluate_game, EndReason, SeatOutcome};
+
+/// How the AI plays tournament games.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TournamentConfig {
+    pub games: usize,
+    /// Games played at once
+    pub threads: usize,
+    /// Budget for each action
+    pub budget: SearchBudget,
+    /// Search with MCTS instead of alpha-beta
+    pub mcts: bool,
+    /// Games still going after this many plies are stopped
+    pub max_plies: usize,
+    /// Plies played randomly at the start so games differ
+    pub random_plies: usize,
+    /// Game `n` uses `seed + n` for every random choice
+    pub seed: u64,
+    pub weights: EvaluationWeights,
+}
+impl Default for TournamentConfig {
+    fn default() -> Self {
+        Self {
+            games: 100,
+            threads: num_cpus::get(),
+            budget: SearchBudget::nodes(500),
+            mcts: false,
+            max_plies: 300,
+            random_plies: 2,
+            seed: 0,
+            weights: EvaluationWeights::default(),
+        }
+    }
+}
+
+/// How a game ended.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum GameEnd {
+    Victory(VictoryCondition),
+    /// Every team but one had no victory condition left it could meet
+    NoConditionPossible,
+    /// The seat to move had no legal move under `DrawRule::NoLegalMove`
+    NoLegalMove,
+    Draw(DrawRule),
+    /// Stopped at `TournamentConfig::max_plies`
+    PlyLimit,
+    /// Nobody could act, such as in hidden placement
+    Stalled,
+}
+impl GameEnd {
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameEnd::Victory(condition) => condition.name(),
+            GameEnd::NoConditionPossible => "NoConditionPossible",
+            GameEnd::NoLegalMove => "NoLegalMove",
+            GameEnd::Draw(rule) => rule.name(),
+            GameEnd::PlyLimit => "PlyLimit",
+            GameEnd::Stalled => "Stalled",
+        }
+    }
+}
+
+/// The result of one tournament game.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct GameResult {
+    /// Outcome for each seat, seats that had not lost are `Playing` if the game was stopped
+    pub outcomes: Vec<SeatOutcome>,
+    pub plies: usize,
+    pub end: GameEnd,
+}
+
+/// Totals over every game played with a ruleset.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TournamentReport {
+    pub ruleset: String,
+    pub games: usize,
+    /// Games won by each seat's team, indexed by seat
+    pub seat_wins: Vec<usize>,
+    /// Games drawn by a draw rule
+    pub draws: usize,
+    /// Games stopped before anybody won or drew, see `GameEnd::PlyLimit` and `GameEnd::Stalled`
+    pub stopped: usize,
+    pub total_plies: usize,
+    /// Games by `GameEnd::name`
+    pub ends: BTreeMap<String, usize>,
+}
+impl TournamentReport {
+    pub fn win_rate(&self, seat: u64) -> f64 {
+        self.rate(self.seat_wins[seat as usize])
+    }
+    pub fn draw_rate(&self) -> f64 {
+        self.rate(self.draws)
+    }
+    pub fn stopped_rate(&self) -> f64 {
+        self.rate(self.stopped)
+    }
+    pub fn average_plies(&self) -> f64 {
+        self.rate(self.total_plies)
+    }
+
+    fn rate(&self, count: usize) -> f64 {
+        if self.games == 0 {
+            0.0
+        } else {
+            count as f64 / self.games as f64
+        }
+    }
+}
+impl Display for TournamentReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}: {} games, {:.1} plies on average", self.ruleset, self.games, self.average_plies())?;
+        for seat in 0..self.seat_wins.len() {
+            writeln!(f, "  seat {} wins {:.1}%", seat, self.win_rate(seat as u64) * 100.0)?;
+        }
+        writeln!(f, "  draws {:.1}%", self.draw_rate() * 100.0)?;
+        writeln!(f, "  stopped {:.1}%", self.stopped_rate() * 100.0)?;
+        for (end, count) in &self.ends {
+            writeln!(f, "  {} {}", end, count)?;
+        }
+        Ok(())
+    }
+}
+
+/// Plays `config.games` AI against AI games of a verified ruleset on `config.threads` threads.
+///
+/// Results only depend on the config, not on how the games are spread over threads, as long as the budget has no
+/// time limit.
+pub fn run_tournament(ruleset: &Ruleset, config: &TournamentConfig) -> BoardResult<TournamentReport> {
+    let start = Arc::new(GameState::new(ruleset)?);
+    let shared_ruleset = Arc::new(ruleset.clone());
+    let next = Arc::new(AtomicUsize::new(0));
+    let threads: Vec<_> = (0..config.threads.max(1))
+        .map(|_| {
+            let (ruleset, start, next, config) = (shared_ruleset.clone(), start.clone(), next.clone(), *config);
+            thread::spawn(move || {
+                let mut results = Vec::new();
+                loop {
+                    let game = next.fetch_add(1, Ordering::Relaxed);
+                    if game >= config.games {
+                        break results;
+                    }
+                    results.push(play_game(&ruleset, GameState::clone(&start), &config, config.seed.wrapping_add(game as u64)));
+                }
+            })
+        })
+        .collect();
+    let mut results = Vec::with_capacity(config.games);
+    for thread in threads {
+        results.extend(thread.join().expect("Tournament thread panicked"));
+    }
+
+    let mut report = TournamentReport {
+        ruleset: ruleset.name.clone(),
+        games: 0,
+        seat_wins: vec![0; ruleset.seats as usize],
+        draws: 0,
+        stopped: 0,
+        total_plies: 0,
+        ends: BTreeMap::new(),
+    };
+    for result in results {
+        report.games += 1;
+        report.total_plies += result.plies;
+        *report.ends.entry(result.end.name().to_string()).or_insert(0) += 1;
+        match result.end {
+            GameEnd::PlyLimit | GameEnd::Stalled => report.stopped += 1,
+            GameEnd::Draw(_) => report.draws += 1,
+            _ => for (seat, outcome) in result.outcomes.iter().enumerate() {
+                if let SeatOutcome::Won(_) = outcome {
+                    report.seat_wins[seat] += 1;
+                }
+            },
+        }
+    }
+    Ok(report)
+}
+
+/// Plays one game from `state` with every seat using the same AI.
+///
+/// With more than two teams the game goes on after a team loses, until a seat wins or draws. Seats of a team that
+/// lost skip their turns.
+pub fn play_game(ruleset: &Ruleset, mut state: GameState, config: &TournamentConfig, seed: u64) -> GameResult {
+    let mut rng = StdRng::seed_from_u64(seed);
+    // Plies played before the state was restarted to skip a turn
+    let mut skipped_plies = 0;
+    loop {
+        let outcomes = evaluate_game(ruleset, &state);
+        let plies = skipped_plies + state.ply();
+        if let Some(end) = outcomes.iter().filter(|outcome| !matches!(outcome, SeatOutcome::Lost(_))).find_map(game_end) {
+            return GameResult { outcomes, plies, end };
+        }
+        let stopped = |end| GameResult {
+            outcomes: outcomes.iter().map(|outcome| if let SeatOutcome::Lost(_) = outcome { outcome.clone() } else { SeatOutcome::Playing }).collect(),
+            plies,
+            end,
+        };
+        if plies >= config.max_plies {
+            return stopped(GameEnd::PlyLimit);
+        }
+        if let SeatOutcome::Lost(_) = outcomes[state.board().current_seat() as usize] {
+            // Draw rules are not checked once a team has lost, so the history can be dropped
+            let mut board = state.board().clone();
+            board.end_turn();
+            skipped_plies = plies;
+            state = GameState::from_board(ruleset, board);
+            continue;
+        }
+        let action = if plies < config.random_plies {
+            let mut actions = state.distinct_actions(ruleset);
+            if actions.is_empty() {
+                None
+            } else {
+                Some(actions.swap_remove(rng.gen_range(0..actions.len())))
+            }
+        } else if config.mcts {
+            let mcts_config = MctsConfig { seed: rng.gen(), ..MctsConfig::default() };
+            mcts::search(ruleset, &mut state, config.budget, mcts_config, &config.weights).action
+        } else {
+            alpha_beta::search(ruleset, &mut state, config.budget, &config.weights).action
+        };
+        match action {
+            None => return stopped(GameEnd::Stalled),
+            Some(action) => state.apply(ruleset, action).expect("Legal action could not be applied"),
+        }
+    }
+}
+
+fn game_end(outcome: &SeatOutcome) -> Option<GameEnd> {
+    match outcome {
+        SeatOutcome::Playing => None,
+        SeatOutcome::Won(reason) | SeatOutcome::Lost(reason) => Some(match reason {
+            EndReason::Victory(condition) => GameEnd::Victory(condition.clone()),
+            EndReason::NoConditionPossible => GameEnd::NoConditionPossible,
+            EndReason::NoLegalMove => GameEnd::NoLegalMove,
+        }),
+        SeatOutcome::Drawn(rule) => Some(GameEnd::Draw(*rule)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::File;
+    use std::time::{Duration, Instant};
+
+    use crate::game::ai::SearchBudget;
+    use crate::game::board::{Board, BoardPiece};
+    use crate::game::Coordinate;
+    use crate::game::ruleset::color::ColorBuiltIn;
+    use crate::game::ruleset::board_type::BoardType;
+    use crate::game::ruleset::Ruleset;
+    use crate::game::ruleset::standard::standard_rules;
+    use crate::game::ruleset::starting_positions::StartingPositions;
+    use crate::game::ruleset::victory_condition::VictoryCondition;
+    use crate::game::state::GameState;
+    use crate::game::victory::SeatOutcome;
+    use crate::tournament::{GameEnd, play_game, run_tournament, TournamentConfig};
+
+    /// Two little pieces each on a 4 by 4 board.
+    fn small() -> Ruleset {
+        let standard = standard_rules();
+        Ruleset {
+            name: "Small".to_string(),
+            pieces: vec![standard.pieces[1].clone()],
+            board_type: BoardType::Rectangular { rows: 4, columns: 4, goal_locations: vec![1, 2] },
+            starting_positions: StartingPositions::NotMirrored(vec![
+                vec![vec![Coordinate::new(1, 1), Coordinate::new(1, 2)]],
+                vec![vec![Coordinate::new(4, 1), Coordinate::new(4, 2)]],
+            ]),
+            victory_conditions: vec![
+                VictoryCondition::AllCaptured,
+                VictoryCondition::GoalCount { amount: 1, valid_pieces: vec![0].into_iter().collect() },
+            ].into_iter().collect(),
+            ..standard
+        }
+    }
+
+    #[test]
+    fn report_totals() {
+        let ruleset = small();
+        ruleset.verify().unwrap();
+        let config = TournamentConfig { games: 6, threads: 3, budget: SearchBudget::depth(2), max_plies: 40, ..TournamentConfig::default() };
+        let report = run_tournament(&ruleset, &config).unwrap();
+        assert_eq!(report.games, 6);
+        assert_eq!(report.seat_wins.iter().sum::<usize>() + report.draws + report.stopped, 6);
+        assert_eq!(report.ends.values().sum::<usize>(), 6);
+        assert!(report.average_plies() > 0.0);
+        assert!(report.to_string().starts_with("Small: 6 games"));
+
+        let single = run_tournament(&ruleset, &TournamentConfig { threads: 1, ..config }).unwrap();
+        assert_eq!(single, report);
+    }
+
+    #[test]
+    fn plays_on_after_a_team_loses() {
+        let mut ruleset = small();
+        ruleset.seats = 3;
+        ruleset.seat_colors.push(ColorBuiltIn::Green.into());
+        let mut board = Board::new(&ruleset);
+        for &(row, column, seat) in &[(1, 1, 0), (1, 2, 0), (4, 1, 1), (4, 2, 1)] {
+            board.place(&ruleset, Coordinate::new(row, column), BoardPiece::new(seat, 0)).unwrap();
+        }
+        // Seat 2 starts without pieces so it has lost, seats 0 and 1 play on and skip its turns
+        let config = TournamentConfig { budget: SearchBudget::depth(2), max_plies: 40, ..TournamentConfig::default() };
+        let result = play_game(&ruleset, GameState::from_board(&ruleset, board), &config, 0);
+        assert!(matches!(result.outcomes[2], SeatOutcome::Lost(_)));
+        assert!(result.plies > 0);
+        assert_ne!(result.end, GameEnd::NoConditionPossible);
+        assert_ne!(result.end, GameEnd::Stalled);
+    }
+
+    #[test]
+    fn example_standard_plies() {
+        // Big pieces can jump back and forth between many pieces here, millions of paths a few plies in
+        let ruleset: Ruleset = serde_json::from_reader(File::open("example/standard_rules.json").unwrap()).unwrap();
+        let config = TournamentConfig { max_plies: 6, random_plies: 0, ..TournamentConfig::default() };
+        let start = Instant::now();
+        let result = play_game(&ruleset, GameState::new(&ruleset).unwrap(), &config, 0);
+        assert_eq!(result.plies, 6);
+        assert!(start.elapsed() < Duration::from_secs(60), "6 plies took {:?}", start.elapsed());
+    }
+}